@@ -0,0 +1,186 @@
+//! Arithmetic Functions
+//!
+//! Number theoretic functions of n, computed from the prime factorization of n.
+//!
+//! The factorization is a slice of (prime, exponent) tuples, which is the shape
+//! returned by primal::Sieve::factor() and math::spf::SpfSieve::factor().
+//! i.e. 360 => [(2, 3), (3, 2), (5, 1)]
+//!
+//! Most of these are [multiplicative functions], f(ab) = f(a)f(b) when
+//! gcd(a,b) = 1, so they are defined by their value at the prime powers.
+//! Any other multiplicative function can be evaluated with [multiplicative].
+//! To compute these for every n up to some limit, see math::divisor_sieve.
+//!
+//! [multiplicative functions]: https://en.wikipedia.org/wiki/Multiplicative_function
+
+use std::ops::Mul;
+
+/// Evaluates the multiplicative function f at n
+///
+/// f is defined by its value at the prime powers, prime_power(p, e, p^e), and
+/// f(1) = one.  This is the same definition used by divisor_sieve::multiplicative_sieve.
+///
+/// # Examples
+/// ```
+/// // d(n), the number of divisors
+/// assert_eq!(multiplicative(&[(2, 3), (3, 2)], 1, |_, e, _| e + 1), 12);
+/// ```
+#[allow(dead_code)]
+pub fn multiplicative<T, F>(factors: &[(usize, usize)], one: T, prime_power: F) -> T
+where
+    T: Mul<Output = T>,
+    F: Fn(usize, u32, usize) -> T,
+{
+    factors.iter().fold(one, |f, &(p, e)| {
+        f * prime_power(p, e as u32, p.pow(e as u32))
+    })
+}
+
+/// Euler's totient function φ(n), the count of numbers up to n that are coprime to n
+///
+/// # Examples
+/// ```
+/// assert_eq!(totient(&[(2, 2), (3, 1)]), 4);  // 1,5,7,11
+/// ```
+#[allow(dead_code)]
+pub fn totient(factors: &[(usize, usize)]) -> usize {
+    multiplicative(factors, 1, |p, _, pe| pe - pe / p)
+}
+
+/// The Möbius function μ(n)
+///
+/// 0 if n is not squarefree, otherwise -1 if n has an odd number of prime
+/// factors and 1 if it has an even number.
+#[allow(dead_code)]
+pub fn mobius(factors: &[(usize, usize)]) -> i32 {
+    multiplicative(factors, 1, |_, e, _| if e == 1 { -1 } else { 0 })
+}
+
+/// The Liouville function λ(n) = (-1)^Ω(n)
+#[allow(dead_code)]
+pub fn liouville(factors: &[(usize, usize)]) -> i32 {
+    if big_omega(factors).is_multiple_of(2) {
+        1
+    } else {
+        -1
+    }
+}
+
+/// ω(n), the number of distinct prime factors of n
+#[allow(dead_code)]
+pub fn omega(factors: &[(usize, usize)]) -> usize {
+    factors.len()
+}
+
+/// Ω(n), the number of prime factors of n, counted with multiplicity
+#[allow(dead_code)]
+pub fn big_omega(factors: &[(usize, usize)]) -> usize {
+    factors.iter().map(|&(_, e)| e).sum()
+}
+
+/// rad(n), the product of the distinct prime factors of n
+///
+/// # Examples
+/// ```
+/// assert_eq!(radical(&[(2, 3), (3, 2), (5, 1)]), 30);
+/// ```
+#[allow(dead_code)]
+pub fn radical(factors: &[(usize, usize)]) -> usize {
+    factors.iter().map(|&(p, _)| p).product()
+}
+
+/// d(n), the number of divisors of n
+///
+/// d(n) = (e1+1)*(e2+1)* ... * (ek+1) where n = p1^e1 * p2^e2 * ... * pk^ek
+pub fn divisor_count(factors: &[(usize, usize)]) -> usize {
+    factors.iter().map(|&(_, e)| e + 1).product()
+}
+
+/// σk(n), the sum of the kth powers of the divisors of n
+///
+/// σ0 is the divisor count and σ1 is the divisor sum.
+///
+/// # Examples
+/// ```
+/// assert_eq!(divisor_sigma(&[(2, 2), (3, 1)], 1), 28);  // 1+2+3+4+6+12
+/// ```
+#[allow(dead_code)]
+pub fn divisor_sigma(factors: &[(usize, usize)], k: u32) -> u128 {
+    multiplicative(factors, 1, |p, e, _| {
+        let pk = (p as u128).pow(k);
+        (0..e).fold(1, |sum, _| sum * pk + 1)
+    })
+}
+
+/// Returns true if no square of a prime divides n
+pub fn is_squarefree(factors: &[(usize, usize)]) -> bool {
+    factors.iter().all(|&(_, e)| e < 2)
+}
+
+/// The squarefree core (or kernel) of n
+///
+/// Every n can be written uniquely as n = core * s^2 where core is squarefree.
+/// The core is the product of the primes with an odd exponent.
+///
+/// # Examples
+/// ```
+/// assert_eq!(squarefree_core(&[(2, 3), (3, 2), (5, 1)]), 10);  // 360 = 10 * 6^2
+/// ```
+#[allow(dead_code)]
+pub fn squarefree_core(factors: &[(usize, usize)]) -> usize {
+    factors
+        .iter()
+        .filter(|&&(_, e)| e % 2 == 1)
+        .map(|&(p, _)| p)
+        .product()
+}
+
+/// The square part of n, i.e. s where n = core * s^2 (see squarefree_core)
+///
+/// # Examples
+/// ```
+/// assert_eq!(square_part(&[(2, 3), (3, 2), (5, 1)]), 6);  // 360 = 10 * 6^2
+/// ```
+#[allow(dead_code)]
+pub fn square_part(factors: &[(usize, usize)]) -> usize {
+    factors.iter().map(|&(p, e)| p.pow(e as u32 / 2)).product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::divisor_sieve;
+    use super::*;
+
+    #[test]
+    pub fn arith_360_test() {
+        let f = [(2, 3), (3, 2), (5, 1)];
+        assert_eq!(totient(&f), 96);
+        assert_eq!(mobius(&f), 0);
+        assert_eq!(liouville(&f), 1);
+        assert_eq!(omega(&f), 3);
+        assert_eq!(big_omega(&f), 6);
+        assert_eq!(radical(&f), 30);
+        assert_eq!(divisor_count(&f), 24);
+        assert_eq!(divisor_sigma(&f, 0), 24);
+        assert_eq!(divisor_sigma(&f, 1), 1170);
+        assert!(!is_squarefree(&f));
+        assert_eq!(squarefree_core(&f) * square_part(&f).pow(2), 360);
+    }
+    #[test]
+    pub fn arith_matches_sieve_test() {
+        let n = 1000;
+        let sieve = primal::Sieve::new(n);
+        let phi = divisor_sieve::totients(n);
+        let mu = divisor_sieve::mobius(n);
+        let sigma = divisor_sieve::divisor_sums(n);
+        for i in 1..=n {
+            let f = sieve.factor(i).unwrap();
+            assert_eq!(totient(&f), phi[i]);
+            assert_eq!(mobius(&f), mu[i] as i32);
+            assert_eq!(divisor_sigma(&f, 1), sigma[i] as u128);
+            assert_eq!(is_squarefree(&f), mu[i] != 0);
+        }
+        assert_eq!(mobius(&[]), 1);
+        assert_eq!(liouville(&[(2, 1), (3, 2)]), -1);
+    }
+}
@@ -0,0 +1,709 @@
+//! Arbitrary Precision Unsigned Integers
+//!
+//! Many Euler problems have answers (or intermediate values) that do not fit
+//! in a u64 or even a u128, e.g. large Fibonacci numbers, factorials, and
+//! counts like the non-bouncy numbers below a googol.
+//!
+//! A BigUint is stored as a vector of 32 bit "limbs", least significant first,
+//! with no trailing (most significant) zero limbs.  Zero is the empty vector.
+//! 32 bit limbs are used so that the product of two limbs fits in a u64.
+//!
+//! * +, -, *, /, % are available for both values and references.  Use the
+//!   references (&a + &b) to avoid cloning in a loop.
+//! * Multiplication is schoolbook for small numbers, and [Karatsuba] for large.
+//! * Division uses Knuth's Algorithm D (The Art of Computer Programming, Vol 2, 4.3.1).
+//! * Numbers are parsed from, and displayed as, decimal strings.
+//!
+//! See the crate [num_bigint] for a more complete (and faster) solution.
+//!
+//! [Karatsuba]: https://en.wikipedia.org/wiki/Karatsuba_algorithm
+//! [num_bigint]: https://crates.io/crates/num-bigint
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Rem, Sub, SubAssign};
+use std::str::FromStr;
+
+/// The number of limbs where Karatsuba multiplication becomes faster than schoolbook
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// Largest power of 10 that fits in a limb, used for decimal conversions
+const DECIMAL_BASE: u32 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+/// An arbitrary precision unsigned integer
+///
+/// # Examples
+/// ```
+/// let a: BigUint = "123456789012345678901234567890".parse().unwrap();
+/// let b = BigUint::from(2u32).pow(100);
+/// assert_eq!((&a * &b / &b), a);
+/// assert_eq!(b.to_string(), "1267650600228229401496703205376");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+/// The error returned when parsing a string that is not a decimal number
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseBigUintError;
+
+impl fmt::Display for ParseBigUintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+
+/// Removes the most significant zero limbs
+fn trim(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+/// Compares two trimmed limb slices
+fn cmp_limbs(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+/// Adds b to a starting at limb offset, a must be long enough to hold the result
+fn add_at(a: &mut Vec<u32>, b: &[u32], offset: usize) {
+    if a.len() < offset + b.len() {
+        a.resize(offset + b.len(), 0);
+    }
+    let mut carry = 0u64;
+    let mut i = offset;
+    for &x in b {
+        let sum = a[i] as u64 + x as u64 + carry;
+        a[i] = sum as u32;
+        carry = sum >> 32;
+        i += 1;
+    }
+    while carry > 0 {
+        if i == a.len() {
+            a.push(0);
+        }
+        let sum = a[i] as u64 + carry;
+        a[i] = sum as u32;
+        carry = sum >> 32;
+        i += 1;
+    }
+}
+
+/// Subtracts b from a in place
+///
+/// # Panics
+///
+/// will panic if b > a
+fn sub_in_place(a: &mut Vec<u32>, b: &[u32]) {
+    let mut borrow = 0i64;
+    for i in 0..a.len() {
+        let x = if i < b.len() { b[i] as i64 } else { 0 };
+        if i >= b.len() && borrow == 0 {
+            break;
+        }
+        let diff = a[i] as i64 - x - borrow;
+        a[i] = diff as u32;
+        borrow = if diff < 0 { 1 } else { 0 };
+    }
+    assert!(
+        borrow == 0 && a.len() >= b.len(),
+        "attempt to subtract with overflow"
+    );
+    trim(a);
+}
+
+/// Returns a * b with the O(n^2) long multiplication taught in school
+fn mul_schoolbook(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut product = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let t = x as u64 * y as u64 + product[i + j] as u64 + carry;
+            product[i + j] = t as u32;
+            carry = t >> 32;
+        }
+        product[i + b.len()] = carry as u32;
+    }
+    trim(&mut product);
+    product
+}
+
+/// Returns a * b, using Karatsuba's method when both numbers are large
+///
+/// Split each number at m limbs, a = a1*B^m + a0 and b = b1*B^m + b0, then
+/// a*b = z2*B^2m + z1*B^m + z0, where z0 = a0*b0, z2 = a1*b1, and
+/// z1 = (a0+a1)(b0+b1) - z0 - z2.  This is 3 multiplications of half the size
+/// rather than 4, so the time is O(n^1.58).
+fn mul_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        return mul_schoolbook(a, b);
+    }
+    let m = a.len().min(b.len()) / 2;
+    let (a0, a1) = a.split_at(m);
+    let (b0, b1) = b.split_at(m);
+    let mut a0 = a0.to_vec();
+    let mut b0 = b0.to_vec();
+    trim(&mut a0);
+    trim(&mut b0);
+    let z0 = mul_limbs(&a0, &b0);
+    let z2 = mul_limbs(a1, b1);
+    add_at(&mut a0, a1, 0);
+    add_at(&mut b0, b1, 0);
+    let mut z1 = mul_limbs(&a0, &b0);
+    sub_in_place(&mut z1, &z0);
+    sub_in_place(&mut z1, &z2);
+    let mut product = z0;
+    add_at(&mut product, &z1, m);
+    add_at(&mut product, &z2, 2 * m);
+    trim(&mut product);
+    product
+}
+
+/// Returns a << shift where shift < 32, with an extra limb for the overflow
+fn shl_bits(a: &[u32], shift: u32) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0u32;
+    for &x in a {
+        result.push((x << shift) | carry);
+        carry = if shift == 0 { 0 } else { x >> (32 - shift) };
+    }
+    result.push(carry);
+    result
+}
+
+/// Returns (u / v, u % v) where v has at least 2 limbs and u >= v
+///
+/// This is long division as done by hand, one limb of the quotient at a time.
+/// Both numbers are first shifted so that the top bit of v is set, then each
+/// quotient limb can be estimated from the top limbs, and the estimate is
+/// at most 2 too large.
+fn div_rem_limbs(u: &[u32], v: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    const B: u64 = 1 << 32;
+    let shift = v[v.len() - 1].leading_zeros();
+    let vn = shl_bits(v, shift);
+    let vn = &vn[..v.len()];
+    let mut un = shl_bits(u, shift);
+    let n = vn.len();
+    let m = u.len() - n;
+    let mut q = vec![0u32; m + 1];
+    for j in (0..=m).rev() {
+        let num = ((un[j + n] as u64) << 32) | un[j + n - 1] as u64;
+        let mut qhat = num / vn[n - 1] as u64;
+        let mut rhat = num % vn[n - 1] as u64;
+        while qhat >= B || qhat * vn[n - 2] as u64 > ((rhat << 32) | un[j + n - 2] as u64) {
+            qhat -= 1;
+            rhat += vn[n - 1] as u64;
+            if rhat >= B {
+                break;
+            }
+        }
+        // un[j..=j+n] -= qhat * vn
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+        for i in 0..n {
+            let p = qhat * vn[i] as u64 + carry;
+            carry = p >> 32;
+            let t = un[i + j] as i64 - borrow - (p & 0xffff_ffff) as i64;
+            un[i + j] = t as u32;
+            borrow = if t < 0 { 1 } else { 0 };
+        }
+        let t = un[j + n] as i64 - borrow - carry as i64;
+        un[j + n] = t as u32;
+        if t < 0 {
+            // qhat was one too large, so add vn back
+            qhat -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let sum = un[i + j] as u64 + vn[i] as u64 + carry;
+                un[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u32);
+        }
+        q[j] = qhat as u32;
+    }
+    // unshift the remainder
+    let mut r: Vec<u32> = (0..n)
+        .map(|i| {
+            let high = if shift == 0 {
+                0
+            } else {
+                un[i + 1] << (32 - shift)
+            };
+            (un[i] >> shift) | high
+        })
+        .collect();
+    trim(&mut q);
+    trim(&mut r);
+    (q, r)
+}
+
+impl BigUint {
+    /// Returns 0
+    pub fn zero() -> BigUint {
+        BigUint { limbs: Vec::new() }
+    }
+
+    /// Returns 1
+    pub fn one() -> BigUint {
+        BigUint { limbs: vec![1] }
+    }
+
+    /// Returns true if self is 0
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// The number of bits needed to represent self (0 for 0)
+    #[allow(dead_code)]
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            None => 0,
+            Some(&top) => self.limbs.len() as u64 * 32 - top.leading_zeros() as u64,
+        }
+    }
+
+    /// Returns self as a u64, or None if it is too large
+    #[allow(dead_code)]
+    pub fn to_u64(&self) -> Option<u64> {
+        self.to_u128().and_then(|x| u64::try_from(x).ok())
+    }
+
+    /// Returns self as a u128, or None if it is too large
+    #[allow(dead_code)]
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0u128, |acc, &x| (acc << 32) | x as u128),
+        )
+    }
+
+    /// Returns self * x
+    pub fn mul_small(&self, x: u32) -> BigUint {
+        let mut result = self.clone();
+        result.mul_small_assign(x);
+        result
+    }
+
+    /// Multiplies self by x in place
+    pub fn mul_small_assign(&mut self, x: u32) {
+        if x == 0 {
+            self.limbs.clear();
+            return;
+        }
+        let mut carry = 0u64;
+        for limb in self.limbs.iter_mut() {
+            let t = *limb as u64 * x as u64 + carry;
+            *limb = t as u32;
+            carry = t >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    /// Adds x to self in place
+    pub fn add_small_assign(&mut self, x: u32) {
+        add_at(&mut self.limbs, &[x], 0);
+        trim(&mut self.limbs);
+    }
+
+    /// Returns (self / d, self % d) for a small divisor
+    ///
+    /// # Panics
+    ///
+    /// will panic if d is zero
+    pub fn div_rem_small(&self, d: u32) -> (BigUint, u32) {
+        assert!(d != 0, "attempt to divide by zero");
+        let mut q = vec![0u32; self.limbs.len()];
+        let mut r = 0u64;
+        for i in (0..self.limbs.len()).rev() {
+            let t = (r << 32) | self.limbs[i] as u64;
+            q[i] = (t / d as u64) as u32;
+            r = t % d as u64;
+        }
+        trim(&mut q);
+        (BigUint { limbs: q }, r as u32)
+    }
+
+    /// Returns (self / d, self % d)
+    ///
+    /// # Examples
+    /// ```
+    /// let n = BigUint::from(10u32).pow(30) + BigUint::from(7u32);
+    /// let (q, r) = n.div_rem(&BigUint::from(10u32).pow(20));
+    /// assert_eq!(q, BigUint::from(10u32).pow(10));
+    /// assert_eq!(r, BigUint::from(7u32));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// will panic if d is zero
+    pub fn div_rem(&self, d: &BigUint) -> (BigUint, BigUint) {
+        assert!(!d.is_zero(), "attempt to divide by zero");
+        if self < d {
+            return (BigUint::zero(), self.clone());
+        }
+        if d.limbs.len() == 1 {
+            let (q, r) = self.div_rem_small(d.limbs[0]);
+            return (q, BigUint::from(r));
+        }
+        let (q, r) = div_rem_limbs(&self.limbs, &d.limbs);
+        (BigUint { limbs: q }, BigUint { limbs: r })
+    }
+
+    /// Returns self ^ exp
+    ///
+    /// Uses exponentiation by squaring.
+    ///
+    /// # Examples
+    /// ```
+    /// assert_eq!(BigUint::from(3u32).pow(4), BigUint::from(81u32));
+    /// ```
+    pub fn pow(&self, exp: u32) -> BigUint {
+        let mut result = BigUint::one();
+        let mut base = self.clone();
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// Returns the sum of the decimal digits of self
+    ///
+    /// # Examples
+    /// ```
+    /// assert_eq!(BigUint::from(2u32).pow(15).digit_sum(), 26);  // 32768
+    /// ```
+    #[allow(dead_code)]
+    pub fn digit_sum(&self) -> u64 {
+        let mut n = self.clone();
+        let mut sum = 0;
+        while !n.is_zero() {
+            let (q, mut r) = n.div_rem_small(DECIMAL_BASE);
+            while r > 0 {
+                sum += (r % 10) as u64;
+                r /= 10;
+            }
+            n = q;
+        }
+        sum
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(x: $t) -> BigUint {
+                    let mut x = x as u128;
+                    let mut limbs = Vec::new();
+                    while x > 0 {
+                        limbs.push(x as u32);
+                        x >>= 32;
+                    }
+                    BigUint { limbs }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering {
+        cmp_limbs(&self.limbs, &other.limbs)
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    /// Parses a string of decimal digits, e.g. "12345678901234567890"
+    fn from_str(s: &str) -> Result<BigUint, ParseBigUintError> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigUintError);
+        }
+        let mut n = BigUint::zero();
+        // the first chunk is short, so that the rest are exactly 9 digits
+        let first = s.len() % DECIMAL_DIGITS;
+        let mut start = 0;
+        let mut end = if first == 0 { DECIMAL_DIGITS } else { first };
+        while start < s.len() {
+            let chunk: u32 = s[start..end].parse().map_err(|_| ParseBigUintError)?;
+            n.mul_small_assign(10u32.pow((end - start) as u32));
+            n.add_small_assign(chunk);
+            start = end;
+            end += DECIMAL_DIGITS;
+        }
+        Ok(n)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+        // collect 9 digit chunks, least significant first
+        let mut chunks = Vec::new();
+        let mut n = self.clone();
+        while !n.is_zero() {
+            let (q, r) = n.div_rem_small(DECIMAL_BASE);
+            chunks.push(r);
+            n = q;
+        }
+        let mut s = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            s.push_str(&format!("{:09}", chunk));
+        }
+        f.pad_integral(true, "", &s)
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn add(self, rhs: &BigUint) -> BigUint {
+        let mut limbs = self.limbs.clone();
+        add_at(&mut limbs, &rhs.limbs, 0);
+        BigUint { limbs }
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+    /// # Panics
+    ///
+    /// will panic if rhs > self
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        let mut limbs = self.limbs.clone();
+        sub_in_place(&mut limbs, &rhs.limbs);
+        BigUint { limbs }
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        BigUint {
+            limbs: mul_limbs(&self.limbs, &rhs.limbs),
+        }
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).1
+    }
+}
+
+/// Implements the owned versions of an operator in terms of the reference version
+macro_rules! forward_binop {
+    ($($trait:ident, $method:ident);*) => {
+        $(
+            impl $trait<BigUint> for BigUint {
+                type Output = BigUint;
+                fn $method(self, rhs: BigUint) -> BigUint {
+                    (&self).$method(&rhs)
+                }
+            }
+
+            impl $trait<&BigUint> for BigUint {
+                type Output = BigUint;
+                fn $method(self, rhs: &BigUint) -> BigUint {
+                    (&self).$method(rhs)
+                }
+            }
+        )*
+    };
+}
+
+forward_binop!(Add, add; Sub, sub; Mul, mul; Div, div; Rem, rem);
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, rhs: &BigUint) {
+        add_at(&mut self.limbs, &rhs.limbs, 0);
+    }
+}
+
+impl AddAssign for BigUint {
+    fn add_assign(&mut self, rhs: BigUint) {
+        *self += &rhs;
+    }
+}
+
+impl SubAssign<&BigUint> for BigUint {
+    fn sub_assign(&mut self, rhs: &BigUint) {
+        sub_in_place(&mut self.limbs, &rhs.limbs);
+    }
+}
+
+impl SubAssign for BigUint {
+    fn sub_assign(&mut self, rhs: BigUint) {
+        *self -= &rhs;
+    }
+}
+
+impl MulAssign<&BigUint> for BigUint {
+    fn mul_assign(&mut self, rhs: &BigUint) {
+        self.limbs = mul_limbs(&self.limbs, &rhs.limbs);
+    }
+}
+
+impl MulAssign for BigUint {
+    fn mul_assign(&mut self, rhs: BigUint) {
+        *self *= &rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A deterministic sequence of large numbers with a mix of limb values
+    fn big(seed: u64, limbs: usize) -> BigUint {
+        let mut x = seed;
+        let mut v: Vec<u32> = (0..limbs)
+            .map(|i| {
+                x = x
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                if i % 7 == 3 {
+                    u32::MAX
+                } else {
+                    (x >> 32) as u32
+                }
+            })
+            .collect();
+        trim(&mut v);
+        BigUint { limbs: v }
+    }
+
+    #[test]
+    pub fn biguint_matches_u128_test() {
+        let values = [
+            0u128,
+            1,
+            2,
+            9,
+            10,
+            u32::MAX as u128,
+            1 << 32,
+            u64::MAX as u128,
+            1 << 100,
+        ];
+        for &a in values.iter() {
+            for &b in values.iter() {
+                let (x, y) = (BigUint::from(a), BigUint::from(b));
+                assert_eq!((&x + &y).to_u128(), a.checked_add(b));
+                assert_eq!(x.cmp(&y), a.cmp(&b));
+                if a >= b {
+                    assert_eq!((&x - &y).to_u128(), Some(a - b));
+                }
+                if let Some(p) = a.checked_mul(b) {
+                    assert_eq!((&x * &y).to_u128(), Some(p));
+                }
+                if let Some(q) = a.checked_div(b) {
+                    assert_eq!((&x / &y).to_u128(), Some(q));
+                    assert_eq!((&x % &y).to_u128(), Some(a % b));
+                }
+            }
+        }
+    }
+    #[test]
+    pub fn biguint_parse_display_test() {
+        for s in [
+            "0",
+            "7",
+            "4294967296",
+            "1000000000",
+            "123456789012345678901234567890123",
+        ]
+        .iter()
+        {
+            let n: BigUint = s.parse().unwrap();
+            assert_eq!(n.to_string(), *s);
+        }
+        assert_eq!("000123".parse::<BigUint>().unwrap(), BigUint::from(123u32));
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigUintError));
+        assert_eq!("12a".parse::<BigUint>(), Err(ParseBigUintError));
+        assert_eq!("-1".parse::<BigUint>(), Err(ParseBigUintError));
+        assert_eq!(format!("{:>5}", BigUint::from(42u32)), "   42");
+        let n = BigUint::from(10u32).pow(50);
+        assert_eq!(n.to_string(), format!("1{}", "0".repeat(50)));
+    }
+    #[test]
+    pub fn biguint_karatsuba_test() {
+        for &(la, lb) in [(40, 40), (64, 33), (100, 250), (257, 129)].iter() {
+            let a = big(la as u64, la);
+            let b = big(lb as u64 + 1000, lb);
+            assert_eq!(
+                &a * &b,
+                BigUint {
+                    limbs: mul_schoolbook(&a.limbs, &b.limbs)
+                }
+            );
+        }
+    }
+    #[test]
+    pub fn biguint_div_rem_test() {
+        for &(la, lb) in [(2, 2), (5, 2), (20, 3), (60, 17), (300, 120), (33, 32)].iter() {
+            let a = big(la as u64 * 3, la);
+            let b = big(lb as u64 * 5 + 1, lb);
+            let (q, r) = a.div_rem(&b);
+            assert!(r < b);
+            assert_eq!(&(&q * &b) + &r, a);
+        }
+        // cases where the quotient estimate needs correcting
+        let b = BigUint::from(u64::MAX);
+        let a = BigUint::from(u64::MAX).pow(3) - BigUint::one();
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(&(&q * &b) + &r, a);
+        let (q, r) = BigUint::from(7u32).div_rem_small(3);
+        assert_eq!((q, r), (BigUint::from(2u32), 1));
+    }
+    #[test]
+    pub fn biguint_pow_test() {
+        // Euler problems 16 and 20
+        assert_eq!(BigUint::from(2u32).pow(1000).digit_sum(), 1366);
+        let factorial = (1..=100u32).fold(BigUint::one(), |f, i| f.mul_small(i));
+        assert_eq!(factorial.digit_sum(), 648);
+        assert_eq!(BigUint::from(2u32).pow(1000).bits(), 1001);
+        assert_eq!(BigUint::zero().pow(0), BigUint::one());
+    }
+}
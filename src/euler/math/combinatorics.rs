@@ -0,0 +1,554 @@
+//! Combinatorics
+//!
+//! Binomial coefficients, factorials, Pascal's triangle and multinomials,
+//! computed without overflowing before the result does.
+//!
+//! The binomial coefficient C(n, k) = n! / (k! (n-k)!) is much smaller than the
+//! factorials, so it is computed with the multiplicative formula
+//!
+//!     C(n, k) = (n-k+1)/1 * (n-k+2)/2 * ... * n/k
+//!
+//! where each partial product is itself a binomial coefficient, C(n-k+i, i).
+//! Before multiplying, the common factor of the partial product and i is
+//! divided out, so the only intermediate value is (partial / g) * ((n-k+i) / (i/g)),
+//! which overflows only if the next partial product does.
+//!
+//! Each function comes in four versions, e.g. binomial (u64), binomial_u128,
+//! and checked_binomial and checked_binomial_u128 which return None if the
+//! result does not fit.
+//!
+//! Questions about the prime factors of huge binomials (is it squarefree, is it
+//! divisible by p) can be answered without computing the binomial:
+//!
+//! * [Legendre's formula] for v_p(n!), the exponent of p in n!
+//! * [Kummer's theorem] for v_p(C(n, k))
+//! * [Lucas' theorem] for C(n, k) mod p
+//! * n! and C(n, k) modulo a prime power p^e
+//!
+//! [Legendre's formula]: https://en.wikipedia.org/wiki/Legendre%27s_formula
+//! [Kummer's theorem]: https://en.wikipedia.org/wiki/Kummer%27s_theorem
+//! [Lucas' theorem]: https://en.wikipedia.org/wiki/Lucas%27s_theorem
+
+use super::modular;
+use num_integer::gcd;
+
+macro_rules! impl_binomial {
+    ($t:ty, $checked:ident, $unchecked:ident, $checked_multi:ident, $unchecked_multi:ident) => {
+        /// Returns C(n, k), the number of ways to choose k items from n,
+        /// or None if the result overflows
+        ///
+        /// C(n, k) is 0 when k > n.
+        #[allow(dead_code)]
+        pub fn $checked(n: $t, k: $t) -> Option<$t> {
+            if k > n {
+                return Some(0);
+            }
+            let k = k.min(n - k);
+            let mut c: $t = 1;
+            for i in 1..=k {
+                // c * (n-k+i) is divisible by i
+                let g = gcd(c, i);
+                c = (c / g).checked_mul((n - k + i) / (i / g))?;
+            }
+            Some(c)
+        }
+
+        /// Returns C(n, k), the number of ways to choose k items from n
+        ///
+        /// # Panics
+        ///
+        /// will panic if the result overflows
+        #[allow(dead_code)]
+        pub fn $unchecked(n: $t, k: $t) -> $t {
+            $checked(n, k).expect("binomial coefficient overflow")
+        }
+
+        /// Returns the multinomial coefficient (k1 + k2 + ... + km)! / (k1! k2! ... km!),
+        /// or None if the result overflows
+        ///
+        /// This is the number of ways to arrange a multiset, e.g. the letters of
+        /// MISSISSIPPI with k = [1, 4, 4, 2].  It is computed as the product of
+        /// binomials C(k1, k1) * C(k1 + k2, k2) * ... * C(k1 + ... + km, km).
+        #[allow(dead_code)]
+        pub fn $checked_multi(k: &[$t]) -> Option<$t> {
+            let mut total: $t = 0;
+            let mut result: $t = 1;
+            for &ki in k {
+                total = total.checked_add(ki)?;
+                result = result.checked_mul($checked(total, ki)?)?;
+            }
+            Some(result)
+        }
+
+        /// Returns the multinomial coefficient (k1 + k2 + ... + km)! / (k1! k2! ... km!)
+        ///
+        /// # Panics
+        ///
+        /// will panic if the result overflows
+        #[allow(dead_code)]
+        pub fn $unchecked_multi(k: &[$t]) -> $t {
+            $checked_multi(k).expect("multinomial coefficient overflow")
+        }
+    };
+}
+
+impl_binomial!(
+    u64,
+    checked_binomial,
+    binomial,
+    checked_multinomial,
+    multinomial
+);
+impl_binomial!(
+    u128,
+    checked_binomial_u128,
+    binomial_u128,
+    checked_multinomial_u128,
+    multinomial_u128
+);
+
+/// Returns the factorials 0!, 1!, ..., n!
+///
+/// # Examples
+/// ```
+/// assert_eq!(factorials(5), vec![1, 1, 2, 6, 24, 120]);
+/// ```
+///
+/// # Panics
+///
+/// will panic if n! does not fit in a u64 (n > 20)
+#[allow(dead_code)]
+pub fn factorials(n: u64) -> Vec<u64> {
+    let mut f = vec![1u64];
+    for i in 1..=n {
+        let next = f[i as usize - 1]
+            .checked_mul(i)
+            .expect("factorial overflow");
+        f.push(next);
+    }
+    f
+}
+
+/// Returns the factorials 0!, 1!, ..., n! as u128
+///
+/// # Panics
+///
+/// will panic if n! does not fit in a u128 (n > 34)
+#[allow(dead_code)]
+pub fn factorials_u128(n: u128) -> Vec<u128> {
+    let mut f = vec![1u128];
+    for i in 1..=n {
+        let next = f[i as usize - 1]
+            .checked_mul(i)
+            .expect("factorial overflow");
+        f.push(next);
+    }
+    f
+}
+
+/// Tables of factorials and inverse factorials modulo a prime p
+///
+/// After O(n) setup, binomial(n, k) mod p takes O(1).  Every n in the table
+/// must be less than p, since n! is 0 (mod p) when n >= p.  For larger n see
+/// Lucas' theorem.
+pub struct FactorialsMod {
+    p: u64,
+    fact: Vec<u64>,
+    inv_fact: Vec<u64>,
+}
+
+#[allow(dead_code)]
+impl FactorialsMod {
+    /// Returns the tables of i! and 1/i! (mod p) for i in 0..=n
+    ///
+    /// # Panics
+    ///
+    /// will panic unless n < p (and p should be prime)
+    pub fn new(n: usize, p: u64) -> FactorialsMod {
+        assert!((n as u64) < p, "n must be less than the prime modulus");
+        let mut fact = vec![1 % p; n + 1];
+        for i in 1..=n {
+            fact[i] = modular::mul_mod(fact[i - 1], i as u64, p);
+        }
+        let mut inv_fact = vec![0; n + 1];
+        inv_fact[n] = modular::inv_mod(fact[n], p).expect("p is not prime");
+        for i in (1..=n).rev() {
+            inv_fact[i - 1] = modular::mul_mod(inv_fact[i], i as u64, p);
+        }
+        FactorialsMod { p, fact, inv_fact }
+    }
+
+    /// Returns n! mod p
+    pub fn factorial(&self, n: usize) -> u64 {
+        self.fact[n]
+    }
+
+    /// Returns 1 / n! mod p
+    pub fn inverse_factorial(&self, n: usize) -> u64 {
+        self.inv_fact[n]
+    }
+
+    /// Returns C(n, k) mod p
+    ///
+    /// # Examples
+    /// ```
+    /// let f = FactorialsMod::new(1000, 1_000_000_007);
+    /// assert_eq!(f.binomial(1000, 500), 159835829);
+    /// ```
+    pub fn binomial(&self, n: usize, k: usize) -> u64 {
+        if k > n {
+            return 0;
+        }
+        let c = modular::mul_mod(self.fact[n], self.inv_fact[k], self.p);
+        modular::mul_mod(c, self.inv_fact[n - k], self.p)
+    }
+}
+
+/// An iterator over the rows of Pascal's triangle
+///
+/// The row is kept in a vector that grows by one each time.  The iterator
+/// ends when the next row would overflow a u64 (after row 67).
+pub struct PascalRows {
+    row: Vec<u64>,
+    done: bool,
+}
+
+impl Iterator for PascalRows {
+    type Item = Vec<u64>;
+    fn next(&mut self) -> Option<Vec<u64>> {
+        if self.done {
+            return None;
+        }
+        let current = self.row.clone();
+        // update in place from the right, so each old value is used before it is replaced
+        self.row.push(1);
+        for i in (1..self.row.len() - 1).rev() {
+            match self.row[i].checked_add(self.row[i - 1]) {
+                Some(x) => self.row[i] = x,
+                None => {
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+        Some(current)
+    }
+}
+
+/// Returns an iterator over the rows of Pascal's triangle, starting with [1]
+///
+/// Row n is C(n, 0), C(n, 1), ..., C(n, n).
+///
+/// # Examples
+/// ```
+/// let rows: Vec<Vec<u64>> = pascal_rows().take(4).collect();
+/// assert_eq!(rows[3], vec![1, 3, 3, 1]);
+/// ```
+pub fn pascal_rows() -> PascalRows {
+    PascalRows {
+        row: vec![1],
+        done: false,
+    }
+}
+
+/// Returns v_p(n!), the exponent of the prime p in n!
+///
+/// Legendre's formula: v_p(n!) = n/p + n/p^2 + n/p^3 + ...
+/// since n/p of the numbers up to n are multiples of p, n/p^2 are multiples of p^2, etc.
+///
+/// # Examples
+/// ```
+/// assert_eq!(factorial_valuation(10, 2), 8);  // 10! = 2^8 * 14175
+/// ```
+pub fn factorial_valuation(n: u64, p: u64) -> u64 {
+    let mut v = 0;
+    let mut n = n;
+    while n > 0 {
+        n /= p;
+        v += n;
+    }
+    v
+}
+
+/// Returns v_p(C(n, k)), the exponent of the prime p in C(n, k)
+///
+/// Kummer's theorem: v_p(C(n, k)) is the number of carries when adding
+/// k and n-k in base p.
+///
+/// # Examples
+/// ```
+/// assert_eq!(binomial_valuation(10, 4, 2), 1);  // C(10, 4) = 210 = 2 * 105
+/// ```
+///
+/// # Panics
+///
+/// will panic if k > n
+pub fn binomial_valuation(n: u64, k: u64, p: u64) -> u64 {
+    assert!(k <= n, "k must not exceed n");
+    let mut a = k;
+    let mut b = n - k;
+    let mut carry = 0;
+    let mut carries = 0;
+    while a > 0 || b > 0 {
+        carry = (a % p + b % p + carry >= p) as u64;
+        carries += carry;
+        a /= p;
+        b /= p;
+    }
+    carries
+}
+
+/// Returns C(n, k) mod p, for a prime p
+///
+/// Lucas' theorem: if n and k are written in base p, then C(n, k) is
+/// the product of C(n_i, k_i) of the digits (mod p).  Each small binomial
+/// is computed with the multiplicative formula and one modular inverse.
+///
+/// # Examples
+/// ```
+/// assert_eq!(binomial_mod_prime(10, 3, 7), 1);  // C(10, 3) = 120 = 17*7 + 1
+/// ```
+#[allow(dead_code)]
+pub fn binomial_mod_prime(n: u64, k: u64, p: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let mut result = 1 % p;
+    let (mut n, mut k) = (n, k);
+    while k > 0 {
+        let (ni, ki) = (n % p, k % p);
+        if ki > ni {
+            return 0;
+        }
+        let ki = ki.min(ni - ki);
+        let mut numerator = 1;
+        let mut denominator = 1;
+        for i in 1..=ki {
+            numerator = modular::mul_mod(numerator, ni - ki + i, p);
+            denominator = modular::mul_mod(denominator, i, p);
+        }
+        let small = modular::mul_mod(numerator, modular::inv_mod(denominator, p).unwrap(), p);
+        result = modular::mul_mod(result, small, p);
+        n /= p;
+        k /= p;
+    }
+    result
+}
+
+/// Returns n! / p^v_p(n!) mod p^e, i.e. n! with all the factors of p removed
+///
+/// The numbers up to n that are not multiples of p repeat in blocks of p^e
+/// (mod p^e), and the multiples of p are p * (1, 2, ... n/p), so
+///
+///     n!_p = (block product)^(n / p^e) * (partial block product) * (n/p)!_p
+///
+/// The block product is -1 (mod p^e), except when p = 2 and e >= 3, when it is 1.
+/// This takes O(p^e + log n) time, so p^e should be small.
+///
+/// # Panics
+///
+/// will panic if p^e overflows a u64
+#[allow(dead_code)]
+pub fn factorial_unit_part_mod(n: u64, p: u64, e: u32) -> u64 {
+    let pe = p.checked_pow(e).expect("p^e overflow");
+    // prefix[i] is the product of the numbers up to i that are coprime to p
+    let mut prefix = vec![1 % pe; pe as usize];
+    for i in 1..pe as usize {
+        let x = if (i as u64).is_multiple_of(p) {
+            1
+        } else {
+            i as u64
+        };
+        prefix[i] = modular::mul_mod(prefix[i - 1], x, pe);
+    }
+    let block = if pe == 1 { 0 } else { prefix[pe as usize - 1] };
+    let mut result = 1 % pe;
+    let mut n = n;
+    while n > 0 {
+        result = modular::mul_mod(result, modular::pow_mod(block, n / pe, pe), pe);
+        result = modular::mul_mod(result, prefix[(n % pe) as usize], pe);
+        n /= p;
+    }
+    result
+}
+
+/// Returns n! mod p^e
+///
+/// # Examples
+/// ```
+/// assert_eq!(factorial_mod_prime_power(10, 3, 3), 0);  // 3^4 divides 10!
+/// assert_eq!(factorial_mod_prime_power(5, 7, 2), 22);  // 120 = 2 * 49 + 22
+/// ```
+#[allow(dead_code)]
+pub fn factorial_mod_prime_power(n: u64, p: u64, e: u32) -> u64 {
+    let v = factorial_valuation(n, p);
+    if v >= e as u64 {
+        return 0;
+    }
+    let pe = p.pow(e);
+    modular::mul_mod(factorial_unit_part_mod(n, p, e), p.pow(v as u32), pe)
+}
+
+/// Returns C(n, k) mod p^e
+///
+/// C(n, k) = p^v * n!_p / (k!_p (n-k)!_p), where v is from Kummer's theorem
+/// and the unit parts are invertible mod p^e.  Combined with crt(), this gives
+/// C(n, k) modulo any number.
+///
+/// # Examples
+/// ```
+/// assert_eq!(binomial_mod_prime_power(10, 3, 2, 3), 0);  // 120 = 15 * 8
+/// assert_eq!(binomial_mod_prime_power(10, 3, 3, 2), 3);  // 120 = 13 * 9 + 3
+/// ```
+#[allow(dead_code)]
+pub fn binomial_mod_prime_power(n: u64, k: u64, p: u64, e: u32) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let v = binomial_valuation(n, k, p);
+    if v >= e as u64 {
+        return 0;
+    }
+    let pe = p.pow(e);
+    let denominator = modular::mul_mod(
+        factorial_unit_part_mod(k, p, e),
+        factorial_unit_part_mod(n - k, p, e),
+        pe,
+    );
+    let unit = modular::mul_mod(
+        factorial_unit_part_mod(n, p, e),
+        modular::inv_mod(denominator, pe).unwrap(),
+        pe,
+    );
+    modular::mul_mod(unit, p.pow(v as u32), pe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn binomial_test() {
+        assert_eq!(binomial(5, 2), 10);
+        assert_eq!(binomial(5, 6), 0);
+        assert_eq!(binomial(0, 0), 1);
+        // Euler 15, lattice paths through a 20x20 grid
+        assert_eq!(binomial(40, 20), 137846528820);
+        // the middle of row 67 is the largest central binomial that fits in a u64
+        assert_eq!(checked_binomial(67, 33), Some(14226520737620288370));
+        assert_eq!(checked_binomial(68, 34), None);
+        // a naive n!/(k!(n-k)!) or n(n-1).../k! would overflow long before this
+        assert_eq!(binomial(1_000_000, 3), 166666166667000000);
+        assert_eq!(
+            binomial_u128(130, 65),
+            binomial_u128(129, 64) + binomial_u128(129, 65)
+        );
+        assert_eq!(checked_binomial_u128(132, 66), None);
+    }
+    #[test]
+    pub fn pascal_rows_test() {
+        let mut count = 0;
+        for (n, row) in pascal_rows().enumerate() {
+            assert_eq!(row.len(), n + 1);
+            for (k, &c) in row.iter().enumerate() {
+                assert_eq!(Some(c), checked_binomial(n as u64, k as u64));
+            }
+            count += 1;
+        }
+        assert_eq!(count, 68);
+    }
+    #[test]
+    pub fn multinomial_test() {
+        // MISSISSIPPI
+        assert_eq!(multinomial(&[1, 4, 4, 2]), 34650);
+        assert_eq!(multinomial(&[]), 1);
+        assert_eq!(multinomial(&[3, 4]), binomial(7, 3));
+        assert_eq!(checked_multinomial(&[20, 20, 20]), None);
+        assert_eq!(multinomial_u128(&[20, 20, 20]), 577831214478475823831865900);
+    }
+    #[test]
+    pub fn factorials_test() {
+        let f = factorials(20);
+        assert_eq!(f[20], 2432902008176640000);
+        assert_eq!(factorials_u128(34)[34] / factorials_u128(33)[33], 34);
+        let p = 1_000_000_007;
+        let fm = FactorialsMod::new(100, p);
+        for n in 0..=100 {
+            if n <= 34 {
+                assert_eq!(fm.factorial(n) as u128, factorials_u128(34)[n] % p as u128);
+            }
+            assert_eq!(
+                modular::mul_mod(fm.factorial(n), fm.inverse_factorial(n), p),
+                1
+            );
+            for k in 0..=n {
+                let expected = binomial_u128(n as u128, k as u128) % p as u128;
+                assert_eq!(fm.binomial(n, k) as u128, expected);
+            }
+        }
+    }
+    #[test]
+    pub fn valuation_test() {
+        let sieve = primal::Sieve::new(200);
+        for n in 0..=30u64 {
+            let f = factorials_u128(n as u128)[n as usize];
+            for p in sieve.primes_from(2).take_while(|&p| p <= 31) {
+                let p = p as u64;
+                let mut expected = 0;
+                let mut x = f;
+                while x.is_multiple_of(p as u128) {
+                    x /= p as u128;
+                    expected += 1;
+                }
+                assert_eq!(factorial_valuation(n, p), expected);
+            }
+        }
+        for n in 0..=100u64 {
+            for k in 0..=n {
+                for &p in [2u64, 3, 5, 7, 97].iter() {
+                    let v = factorial_valuation(n, p)
+                        - factorial_valuation(k, p)
+                        - factorial_valuation(n - k, p);
+                    assert_eq!(binomial_valuation(n, k, p), v);
+                }
+            }
+        }
+    }
+    #[test]
+    pub fn binomial_mod_test() {
+        for n in 0..=100u64 {
+            for k in 0..=n + 1 {
+                let c = binomial_u128(n as u128, k as u128);
+                for &p in [2u64, 3, 5, 7, 11, 101].iter() {
+                    assert_eq!(binomial_mod_prime(n, k, p) as u128, c % p as u128);
+                }
+                for &(p, e) in [(2u64, 1u32), (2, 3), (2, 6), (3, 3), (5, 2), (7, 2)].iter() {
+                    let pe = p.pow(e) as u128;
+                    assert_eq!(binomial_mod_prime_power(n, k, p, e) as u128, c % pe);
+                }
+            }
+        }
+        // Lucas' theorem with n and k far too large for a table
+        let p = 1_000_000_007;
+        let fm = FactorialsMod::new(1000, p);
+        assert_eq!(
+            binomial_mod_prime(p + 1000, p + 500, p),
+            fm.binomial(1000, 500)
+        );
+    }
+    #[test]
+    pub fn factorial_mod_test() {
+        for n in 0..=34u64 {
+            let f = factorials_u128(34)[n as usize];
+            for &(p, e) in [(2u64, 1u32), (2, 5), (3, 4), (5, 3), (7, 2), (13, 1)].iter() {
+                let pe = p.pow(e) as u128;
+                assert_eq!(factorial_mod_prime_power(n, p, e) as u128, f % pe);
+                let mut unit = f;
+                while unit.is_multiple_of(p as u128) {
+                    unit /= p as u128;
+                }
+                assert_eq!(factorial_unit_part_mod(n, p, e) as u128, unit % pe);
+            }
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Digits
+//!
+//! Functions on the digits of a number in any base (2 or more).
+//!
+//! The digits are produced from the least significant, since that is the
+//! order that repeated division finds them.  Use to_digits() to get them with
+//! the most significant first, as they are written.
+//!
+//! The number 0 has one digit, 0.
+
+/// An iterator over the digits of a number, least significant first
+pub struct Digits {
+    n: usize,
+    base: usize,
+    done: bool,
+}
+
+impl Iterator for Digits {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        if self.done {
+            return None;
+        }
+        let digit = self.n % self.base;
+        self.n /= self.base;
+        self.done = self.n == 0;
+        Some(digit)
+    }
+}
+
+/// Returns an iterator over the digits of n in base, least significant first
+///
+/// # Examples
+/// ```
+/// let d: Vec<usize> = digits(1234, 10).collect();
+/// assert_eq!(d, vec![4, 3, 2, 1]);
+/// ```
+///
+/// # Panics
+///
+/// will panic if base is less than 2
+pub fn digits(n: usize, base: usize) -> Digits {
+    assert!(base >= 2, "base must be at least 2");
+    Digits {
+        n,
+        base,
+        done: false,
+    }
+}
+
+/// Returns the digits of n in base, most significant first
+///
+/// # Examples
+/// ```
+/// assert_eq!(to_digits(6, 2), vec![1, 1, 0]);
+/// ```
+#[allow(dead_code)]
+pub fn to_digits(n: usize, base: usize) -> Vec<usize> {
+    let mut v: Vec<usize> = digits(n, base).collect();
+    v.reverse();
+    v
+}
+
+/// Returns the number with the given digits in base, most significant first
+///
+/// This is the inverse of to_digits().
+///
+/// # Examples
+/// ```
+/// assert_eq!(from_digits(&[1, 2, 3], 10), 123);
+/// assert_eq!(from_digits(&[1, 1, 0], 2), 6);
+/// ```
+#[allow(dead_code)]
+pub fn from_digits(digits: &[usize], base: usize) -> usize {
+    digits.iter().fold(0, |n, &d| n * base + d)
+}
+
+/// Returns the number of digits in n
+///
+/// # Examples
+/// ```
+/// assert_eq!(count(777, 10), 3);
+/// assert_eq!(count(10, 10), 2);
+/// assert_eq!(count(0, 10), 1);
+/// ```
+pub fn count(n: usize, base: usize) -> u32 {
+    digits(n, base).count() as u32
+}
+
+/// Returns the sum of the digits of n
+///
+/// # Examples
+/// ```
+/// assert_eq!(sum(78, 10), 15);
+/// assert_eq!(sum(102000, 10), 3);
+/// ```
+pub fn sum(n: usize, base: usize) -> usize {
+    digits(n, base).sum()
+}
+
+/// Returns the number with the digits of n in reverse order
+///
+/// Trailing zeros of n are lost, i.e. reverse(120) = 21.
+///
+/// # Examples
+/// ```
+/// assert_eq!(reverse(678, 10), 876);
+/// ```
+pub fn reverse(n: usize, base: usize) -> usize {
+    digits(n, base).fold(0, |r, d| r * base + d)
+}
+
+/// Returns the number with the digits of a followed by the digits of b
+///
+/// # Examples
+/// ```
+/// assert_eq!(concat(12, 345, 10), 12345);
+/// assert_eq!(concat(12, 0, 10), 120);
+/// ```
+///
+/// # Panics
+///
+/// will panic if the result overflows
+#[allow(dead_code)]
+pub fn concat(a: usize, b: usize, base: usize) -> usize {
+    a * base.pow(count(b, base)) + b
+}
+
+/// Returns true if n reads the same in both directions
+///
+/// # Examples
+/// ```
+/// assert!(is_palindrome(9009, 10));
+/// assert!(is_palindrome(585, 2));  // 1001001001
+/// assert!(!is_palindrome(10, 10));
+/// ```
+#[allow(dead_code)]
+pub fn is_palindrome(n: usize, base: usize) -> bool {
+    let d: Vec<usize> = digits(n, base).collect();
+    d.iter().eq(d.iter().rev())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn count_test() {
+        assert_eq!(count(0, 10), 1);
+        assert_eq!(count(9, 10), 1);
+        assert_eq!(count(10, 10), 2);
+        assert_eq!(count(12345678, 10), 8);
+        assert_eq!(count(255, 16), 2);
+        assert_eq!(count(256, 2), 9);
+        assert_eq!(count(usize::MAX, 10), 20);
+    }
+    #[test]
+    pub fn sum_test() {
+        assert_eq!(sum(4, 10), 4);
+        assert_eq!(sum(78, 10), 15);
+        assert_eq!(sum(102000, 10), 3);
+        assert_eq!(sum(255, 2), 8);
+    }
+    #[test]
+    pub fn reverse_test() {
+        assert_eq!(reverse(12345678, 10), 87654321);
+        assert_eq!(reverse(1200, 10), 21);
+        assert_eq!(reverse(0, 10), 0);
+        assert_eq!(reverse(0b1101, 2), 0b1011);
+    }
+    #[test]
+    pub fn digits_round_trip_test() {
+        for &base in [2, 3, 10, 16].iter() {
+            for n in 0..2000 {
+                assert_eq!(from_digits(&to_digits(n, base), base), n);
+                assert_eq!(concat(n, 1, base), n * base + 1);
+            }
+        }
+        assert_eq!(concat(0, 0, 10), 0);
+    }
+    #[test]
+    pub fn is_palindrome_test() {
+        // Euler problem 36, palindromic in base 10 and base 2
+        let total: usize = (1..1_000_000)
+            .filter(|&n| is_palindrome(n, 10) && is_palindrome(n, 2))
+            .sum();
+        assert_eq!(total, 872187);
+        assert!(is_palindrome(0, 10));
+    }
+}
@@ -0,0 +1,199 @@
+//! Divisor Function Sieves
+//!
+//! Functions that fill a table with a number theoretic function for every
+//! n in 0..=N in a single pass, rather than factoring each n separately.
+//!
+//! * d(n), the number of divisors of n
+//! * σ(n), the sum of the divisors of n
+//! * σk(n), the sum of the kth powers of the divisors of n
+//! * φ(n), Euler's totient function
+//! * μ(n), the Möbius function
+//!
+//! These are all [multiplicative functions], so they are built with a linear
+//! sieve which can evaluate any multiplicative function in O(N).  This is
+//! several times faster than adding each divisor to all of its multiples.
+//!
+//! The value for n = 0 is meaningless, but it is kept in the tables so that
+//! the table can be indexed by n.
+//!
+//! [multiplicative functions]: https://en.wikipedia.org/wiki/Multiplicative_function
+
+use std::ops::Mul;
+
+/// Returns a table of d(i), the number of divisors of i, for i in 0..=n
+///
+/// # Examples
+/// ```
+/// let d = divisor_counts(12);
+/// assert_eq!(d[12], 6);  // 1,2,3,4,6,12
+/// ```
+pub fn divisor_counts(n: usize) -> Vec<u32> {
+    multiplicative_sieve(n, 1, |_, e, _| e + 1)
+}
+
+/// Returns a table of σ(i), the sum of the divisors of i, for i in 0..=n
+///
+/// # Examples
+/// ```
+/// let sigma = divisor_sums(12);
+/// assert_eq!(sigma[12], 28);  // 1+2+3+4+6+12
+/// ```
+#[allow(dead_code)]
+pub fn divisor_sums(n: usize) -> Vec<u64> {
+    // σ(p^e) = 1 + p + p^2 + ... + p^e = (p^(e+1) - 1)/(p - 1)
+    multiplicative_sieve(n, 1, |p, _, pe| {
+        ((pe as u64) * (p as u64) - 1) / (p as u64 - 1)
+    })
+}
+
+/// Returns a table of σk(i), the sum of the kth powers of the divisors of i, for i in 0..=n
+///
+/// σ0 is the divisor count and σ1 is the divisor sum.
+///
+/// # Examples
+/// ```
+/// let sigma2 = divisor_power_sums(4, 2);
+/// assert_eq!(sigma2[4], 21);  // 1+4+16
+/// ```
+#[allow(dead_code)]
+pub fn divisor_power_sums(n: usize, k: u32) -> Vec<u128> {
+    multiplicative_sieve(n, 1, |p, e, _| {
+        let pk = (p as u128).pow(k);
+        (0..e).fold(1, |sum, _| sum * pk + 1)
+    })
+}
+
+/// Returns a table of f(i) for i in 0..=n, where f is a multiplicative function
+///
+/// f is defined by its value at the prime powers, prime_power(p, e, p^e), and
+/// f(1) = one. Since f(ab) = f(a)f(b) when gcd(a,b) = 1, every other value
+/// is the product of the values at the prime powers.
+///
+/// This uses a linear sieve, so each i is visited once as p * (i/p) where p
+/// is the smallest prime factor of i.
+///
+/// # Examples
+/// ```
+/// // d(n), the number of divisors
+/// let d = multiplicative_sieve(12, 1, |_, e, _| e + 1);
+/// assert_eq!(d[12], 6);
+/// ```
+///
+/// # Panics
+///
+/// will panic if n does not fit in a u32
+pub fn multiplicative_sieve<T, F>(n: usize, one: T, prime_power: F) -> Vec<T>
+where
+    T: Copy + Mul<Output = T>,
+    F: Fn(usize, u32, usize) -> T,
+{
+    assert!(n <= u32::MAX as usize, "{} is too large for the sieve", n);
+    let mut values = vec![one; n + 1];
+    // smallest prime factor, the power of that prime in i, and its exponent
+    let mut spf = vec![0u32; n + 1];
+    let mut spf_power = vec![0u32; n + 1];
+    let mut spf_exp = vec![0u8; n + 1];
+    let mut primes: Vec<u32> = Vec::new();
+    for i in 2..=n {
+        if spf[i] == 0 {
+            spf[i] = i as u32;
+            spf_power[i] = i as u32;
+            spf_exp[i] = 1;
+            primes.push(i as u32);
+        }
+        for &p in primes.iter() {
+            let m = i * p as usize;
+            if p > spf[i] || m > n {
+                break;
+            }
+            spf[m] = p;
+            if p == spf[i] {
+                spf_power[m] = spf_power[i] * p;
+                spf_exp[m] = spf_exp[i] + 1;
+            } else {
+                spf_power[m] = p;
+                spf_exp[m] = 1;
+            }
+        }
+        let pe = spf_power[i] as usize;
+        values[i] = if pe == i {
+            prime_power(spf[i] as usize, spf_exp[i] as u32, pe)
+        } else {
+            values[i / pe] * values[pe]
+        };
+    }
+    values
+}
+
+/// Returns a table of φ(i), the count of numbers up to i that are coprime to i, for i in 0..=n
+///
+/// φ(p^e) = p^e - p^(e-1)
+///
+/// # Examples
+/// ```
+/// let phi = totients(12);
+/// assert_eq!(phi[12], 4);  // 1,5,7,11
+/// ```
+#[allow(dead_code)]
+pub fn totients(n: usize) -> Vec<usize> {
+    multiplicative_sieve(n, 1, |p, _, pe| pe - pe / p)
+}
+
+/// Returns a table of μ(i), the Möbius function, for i in 0..=n
+///
+/// μ(i) is 0 if i is not squarefree, otherwise it is -1 if i has an odd number
+/// of prime factors and 1 if it has an even number.
+///
+/// # Examples
+/// ```
+/// let mu = mobius(30);
+/// assert_eq!(mu[30], -1);  // 2*3*5
+/// assert_eq!(mu[12], 0);
+/// ```
+#[allow(dead_code)]
+pub fn mobius(n: usize) -> Vec<i8> {
+    multiplicative_sieve(n, 1, |_, e, _| if e == 1 { -1 } else { 0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn divisors(n: usize) -> impl Iterator<Item = usize> {
+        (1..=n).filter(move |d| n.is_multiple_of(*d))
+    }
+
+    #[test]
+    pub fn divisor_tables_test() {
+        let n = 500;
+        let d = divisor_counts(n);
+        let sigma = divisor_sums(n);
+        let sigma2 = divisor_power_sums(n, 2);
+        for i in 1..=n {
+            assert_eq!(d[i] as usize, divisors(i).count());
+            assert_eq!(sigma[i] as usize, divisors(i).sum::<usize>());
+            assert_eq!(
+                sigma2[i] as usize,
+                divisors(i).map(|x| x * x).sum::<usize>()
+            );
+        }
+    }
+    #[test]
+    pub fn totients_test() {
+        let n = 500;
+        let phi = totients(n);
+        for (i, &phi_i) in phi.iter().enumerate().skip(1) {
+            let count = (1..=i).filter(|&j| num_integer::gcd(i, j) == 1).count();
+            assert_eq!(phi_i, count);
+        }
+    }
+    #[test]
+    pub fn mobius_test() {
+        let mu = mobius(30);
+        let expected: [i8; 30] = [
+            1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0, -1, 1, 1, 0, -1, 0, -1, 0, 1, 1, -1, 0, 0, 1,
+            0, 0, -1, -1,
+        ];
+        assert_eq!(mu[1..].to_vec(), expected.to_vec());
+    }
+}
@@ -0,0 +1,165 @@
+//! Divisors
+//!
+//! Generate the divisors of a number from its prime factorization.
+//!
+//! The factorization is a slice of (prime, exponent) tuples, which is the shape
+//! returned by primal::Sieve::factor() and math::spf::SpfSieve::factor().
+//! i.e. 36 => [(2, 2), (3, 2)]
+//!
+//! Rather than a recursive power set, the divisors are generated by counting
+//! through the exponents like an odometer, each exponent i "rolls over" after
+//! e_i, and carries to the next prime.  Each divisor is generated exactly once,
+//! so there is no need for a HashSet, and the only allocation is the small
+//! vector of exponents.
+
+use num_integer::Roots;
+
+/// Returns the number n from its prime factorization
+pub fn product(factors: &[(usize, usize)]) -> usize {
+    factors.iter().map(|&(p, e)| p.pow(e as u32)).product()
+}
+
+/// An iterator over the unique divisors of a number (in no particular order)
+///
+/// Only divisors that do not exceed limit are produced.  Since increasing an
+/// exponent only makes the divisor bigger, a digit of the odometer rolls
+/// over as soon as it exceeds the limit.
+pub struct Divisors<'a> {
+    factors: &'a [(usize, usize)],
+    exponents: Vec<usize>,
+    current: usize,
+    limit: usize,
+    done: bool,
+}
+
+impl<'a> Divisors<'a> {
+    fn advance(&mut self) {
+        for (i, &(p, e)) in self.factors.iter().enumerate() {
+            if self.exponents[i] < e {
+                if let Some(next) = self.current.checked_mul(p) {
+                    if next <= self.limit {
+                        self.exponents[i] += 1;
+                        self.current = next;
+                        return;
+                    }
+                }
+            }
+            // roll over this digit and carry to the next
+            self.current /= p.pow(self.exponents[i] as u32);
+            self.exponents[i] = 0;
+        }
+        self.done = true;
+    }
+}
+
+impl<'a> Iterator for Divisors<'a> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        if self.done {
+            return None;
+        }
+        let divisor = self.current;
+        self.advance();
+        Some(divisor)
+    }
+}
+
+/// Returns an iterator over all the divisors of a number (in no particular order)
+///
+/// # Examples
+/// ```
+/// let mut d: Vec<usize> = divisors(&[(2, 2), (3, 1)]).collect();
+/// d.sort();
+/// assert_eq!(d, vec![1, 2, 3, 4, 6, 12]);
+/// ```
+#[allow(dead_code)]
+pub fn divisors(factors: &[(usize, usize)]) -> Divisors<'_> {
+    divisors_up_to(factors, usize::MAX)
+}
+
+/// Returns an iterator over the divisors of a number that do not exceed limit
+///
+/// The search is pruned, so this is much faster than filtering all the divisors
+/// when the limit is small.
+pub fn divisors_up_to(factors: &[(usize, usize)], limit: usize) -> Divisors<'_> {
+    Divisors {
+        factors,
+        exponents: vec![0; factors.len()],
+        current: 1,
+        limit,
+        done: limit == 0,
+    }
+}
+
+/// Returns all the divisors of a number in ascending order
+///
+/// # Examples
+/// ```
+/// assert_eq!(sorted_divisors(&[(2, 2), (3, 1)]), vec![1, 2, 3, 4, 6, 12]);
+/// ```
+#[allow(dead_code)]
+pub fn sorted_divisors(factors: &[(usize, usize)]) -> Vec<usize> {
+    let mut v: Vec<usize> = divisors(factors).collect();
+    v.sort_unstable();
+    v
+}
+
+/// Returns an iterator over the "small" divisors of n, i.e. d where d*d <= n
+///
+/// All the divisors of 12 are 1,2,3,4,6,12.  The small divisors are 1,2,3.
+/// The remaining divisors are n/d for each small divisor d.
+#[allow(dead_code)]
+pub fn small_divisors(factors: &[(usize, usize)]) -> Divisors<'_> {
+    divisors_up_to(factors, product(factors).sqrt())
+}
+
+/// Returns an iterator over the divisor pairs (d, n/d) where d <= n/d
+///
+/// If n is a square, then the last pair will be (sqrt(n), sqrt(n)).
+///
+/// # Examples
+/// ```
+/// let mut pairs: Vec<(usize, usize)> = divisor_pairs(&[(2, 2), (3, 1)]).collect();
+/// pairs.sort();
+/// assert_eq!(pairs, vec![(1, 12), (2, 6), (3, 4)]);
+/// ```
+pub fn divisor_pairs(factors: &[(usize, usize)]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let n = product(factors);
+    divisors_up_to(factors, n.sqrt()).map(move |d| (d, n / d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(n: usize) -> Vec<usize> {
+        (1..=n).filter(|d| n.is_multiple_of(*d)).collect()
+    }
+
+    #[test]
+    pub fn divisors_test() {
+        let sieve = primal::Sieve::new(1000);
+        for n in 1..=1000 {
+            let factors = sieve.factor(n).unwrap();
+            assert_eq!(sorted_divisors(&factors), brute_force(n));
+        }
+    }
+    #[test]
+    pub fn small_divisors_test() {
+        let sieve = primal::Sieve::new(1000);
+        for n in 1..=1000 {
+            let factors = sieve.factor(n).unwrap();
+            let mut small: Vec<usize> = small_divisors(&factors).collect();
+            small.sort_unstable();
+            let expected: Vec<usize> = brute_force(n).into_iter().filter(|d| d * d <= n).collect();
+            assert_eq!(small, expected);
+        }
+    }
+    #[test]
+    pub fn divisor_pairs_test() {
+        let mut pairs: Vec<(usize, usize)> = divisor_pairs(&[(2, 2), (3, 2)]).collect();
+        pairs.sort_unstable();
+        assert_eq!(pairs, vec![(1, 36), (2, 18), (3, 12), (4, 9), (6, 6)]);
+        assert_eq!(divisor_pairs(&[]).collect::<Vec<_>>(), vec![(1, 1)]);
+    }
+}
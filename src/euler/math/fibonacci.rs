@@ -8,6 +8,34 @@
 //! The common definitions (per wikipedia) start with 0, 1:
 //!
 //!   0, 1, 1, 2, 3, 5, 8, ...
+//!
+//! The functions that count up to the nth number take O(n) steps.  The fast
+//! doubling functions use the identities
+//!
+//!   F(2k) = F(k) (2 F(k+1) - F(k))
+//!   F(2k+1) = F(k)^2 + F(k+1)^2
+//!
+//! to go from (F(k), F(k+1)) to F(2k) or F(2k+1) in one step, so they take
+//! O(log n) steps.  There are versions for u128 (up to F(186)), modulo m
+//! (for any n), and BigUint.
+//!
+//! See recurrence.rs for the same iterator with other coefficients and seeds
+//! (Lucas, Pell, tribonacci, ...).
+//!
+//! The sequence modulo m repeats, with a period called the [Pisano period].
+//!
+//! Every positive integer is a sum of non-consecutive Fibonacci numbers in
+//! exactly one way, its [Zeckendorf representation].  A number n is itself a
+//! Fibonacci number if 5n^2 + 4 or 5n^2 - 4 is a perfect square.
+//!
+//! [Pisano period]: https://en.wikipedia.org/wiki/Pisano_period
+//! [Zeckendorf representation]: https://en.wikipedia.org/wiki/Zeckendorf%27s_theorem
+
+use super::divisors;
+use super::modular;
+use super::primality;
+use super::BigUint;
+use num_integer::{lcm, Roots};
 
 /// Returns the nth Fibonacci number, starting at 0
 ///
@@ -34,6 +62,8 @@ pub fn fib(n: usize) -> usize {
 ///
 /// A loop based solution which counts upto the requested number.
 /// This is a more efficient solution than the recursive method.
+/// fibonacci(93) is the largest that fits in a 64 bit usize,
+/// use fibonacci_big() for larger n.
 ///
 /// # Examples
 /// ```
@@ -55,6 +85,25 @@ pub fn fibonacci(n: usize) -> usize {
     x.1
 }
 
+/// Returns the nth Fibonacci number, starting at 0, for any n
+///
+/// The same loop as fibonacci(), but with arbitrary precision.
+///
+/// # Examples
+/// ```
+/// assert_eq!(fibonacci_big(6), BigUint::from(8u32));
+/// assert_eq!(fibonacci_big(100).to_string(), "354224848179261915075");
+/// ```
+#[allow(dead_code)]
+pub fn fibonacci_big(n: usize) -> BigUint {
+    let mut x = (BigUint::zero(), BigUint::one());
+    for _ in 0..n {
+        let next = &x.0 + &x.1;
+        x = (x.1, next);
+    }
+    x.0
+}
+
 /// Returns a vector with the first n Fibonacci numbers, starting at 0
 ///
 /// Similar in performance to the loop based solution.
@@ -164,6 +213,311 @@ pub fn iter2() -> impl Iterator<Item = usize> {
     })
 }
 
+/// Returns (F(n), F(n+1)) for n <= 93, by fast doubling
+fn fib_pair(n: u64) -> (u128, u128) {
+    let mut f = (0, 1);
+    for bit in (0..64 - n.leading_zeros()).rev() {
+        let (a, b) = f;
+        let even = a * (2 * b - a);
+        let odd = a * a + b * b;
+        f = if (n >> bit) & 1 == 1 {
+            (odd, even + odd)
+        } else {
+            (even, odd)
+        };
+    }
+    f
+}
+
+/// Returns the nth Fibonacci number, starting at 0, in O(log n) steps
+///
+/// Uses fast doubling from (F(n/2), F(n/2+1)).  F(186) is the largest that
+/// fits in a u128.
+///
+/// # Examples
+/// ```
+/// assert_eq!(fib_fast(6), 8);
+/// assert_eq!(fib_fast(100), 354224848179261915075);
+/// ```
+///
+/// # Panics
+///
+/// will panic if n > 186
+#[allow(dead_code)]
+pub fn fib_fast(n: u64) -> u128 {
+    assert!(n <= 186, "F({}) overflows a u128", n);
+    let (a, b) = fib_pair(n / 2);
+    if n.is_multiple_of(2) {
+        a * (2 * b - a)
+    } else {
+        a * a + b * b
+    }
+}
+
+/// Returns F(n) mod m, in O(log n) steps
+///
+/// The same fast doubling as fib_fast(), with every step reduced mod m,
+/// so n can be any u64.
+///
+/// # Examples
+/// ```
+/// assert_eq!(fib_mod(10, 7), 55 % 7);
+/// assert_eq!(fib_mod(1_000_000_000_000_000_000, 1_000_000_007), 209783453);
+/// ```
+#[allow(dead_code)]
+pub fn fib_mod(n: u64, m: u64) -> u64 {
+    let mut f = (0, 1 % m);
+    for bit in (0..64 - n.leading_zeros()).rev() {
+        let (a, b) = f;
+        let twice_b_minus_a = ((2 * b as u128 + (m - a) as u128) % m as u128) as u64;
+        let even = modular::mul_mod(a, twice_b_minus_a, m);
+        let odd = ((modular::mul_mod(a, a, m) as u128 + modular::mul_mod(b, b, m) as u128)
+            % m as u128) as u64;
+        f = if (n >> bit) & 1 == 1 {
+            (odd, ((even as u128 + odd as u128) % m as u128) as u64)
+        } else {
+            (even, odd)
+        };
+    }
+    f.0
+}
+
+/// Returns the nth Fibonacci number, starting at 0, for any n, in O(log n) steps
+///
+/// The same fast doubling as fib_fast(), with arbitrary precision.  The
+/// numbers double in length each step, so the time is dominated by the last
+/// few multiplications.
+///
+/// # Examples
+/// ```
+/// assert_eq!(fib_fast_big(100).to_string(), "354224848179261915075");
+/// ```
+#[allow(dead_code)]
+pub fn fib_fast_big(n: u64) -> BigUint {
+    let mut f = (BigUint::zero(), BigUint::one());
+    for bit in (0..64 - n.leading_zeros()).rev() {
+        let (a, b) = f;
+        let even = &a * &(&(&b + &b) - &a);
+        let odd = &(&a * &a) + &(&b * &b);
+        f = if (n >> bit) & 1 == 1 {
+            let next = &even + &odd;
+            (odd, next)
+        } else {
+            (even, odd)
+        };
+    }
+    f.0
+}
+
+/// Returns the Pisano period π(m), the period of the Fibonacci numbers mod m
+///
+/// π is found from the prime factors of m, since π(m) is the lcm of π(p^e)
+/// for each prime power p^e in m.  π(p^e) divides p^(e-1) π(p), and π(p)
+/// divides p-1 if p = ±1 mod 5, or 2(p+1) if p = ±2 mod 5 (π(2) = 3 and
+/// π(5) = 20).  The period is the smallest divisor d of that bound where
+/// F(d) = 0 and F(d+1) = 1 (mod p^e), which fib_mod() checks in O(log d).
+///
+/// # Examples
+/// ```
+/// assert_eq!(pisano_period(10), 60);  // the last digits repeat every 60
+/// assert_eq!(pisano_period(1_000_000_007), 2_000_000_016);
+/// ```
+///
+/// # Panics
+///
+/// will panic if m is 0, or if the period overflows a u64
+#[allow(dead_code)]
+pub fn pisano_period(m: u64) -> u64 {
+    assert!(m > 0, "the modulus must be positive");
+    primality::factor(m)
+        .into_iter()
+        .map(|(p, e)| {
+            let pe = p.pow(e as u32);
+            let base = match p {
+                2 => 3,
+                5 => 20,
+                p if p % 5 == 1 || p % 5 == 4 => p - 1,
+                p => 2 * (p + 1),
+            };
+            let bound = p
+                .pow(e as u32 - 1)
+                .checked_mul(base)
+                .expect("pisano period overflow");
+            let factors: Vec<(usize, usize)> = primality::factor(bound)
+                .into_iter()
+                .map(|(q, k)| (q as usize, k))
+                .collect();
+            divisors::sorted_divisors(&factors)
+                .into_iter()
+                .map(|d| d as u64)
+                .find(|&d| fib_mod(d, pe) == 0 && fib_mod(d + 1, pe) == 1 % pe)
+                .unwrap()
+        })
+        .fold(1, lcm)
+}
+
+/// Returns true if n is a Fibonacci number
+///
+/// n is a Fibonacci number if and only if 5n^2 + 4 or 5n^2 - 4 is a perfect square.
+/// Near u64::MAX, where 5n^2 does not fit in a u128, n is compared with fib_index().
+///
+/// # Examples
+/// ```
+/// assert!(is_fibonacci(144));
+/// assert!(!is_fibonacci(100));
+/// ```
+#[allow(dead_code)]
+pub fn is_fibonacci(n: u64) -> bool {
+    let is_square = |x: u128| {
+        let r = x.sqrt();
+        r * r == x
+    };
+    match (n as u128 * n as u128).checked_mul(5) {
+        Some(x) => is_square(x + 4) || (x >= 4 && is_square(x - 4)),
+        // 5n^2 overflows a u128 for the last few Fibonacci numbers
+        None => fib_fast(fib_index(n)) == n as u128,
+    }
+}
+
+/// Returns the index of the largest Fibonacci number that does not exceed n
+///
+/// If n is a Fibonacci number, this is its index (2 for n = 1).  The index is
+/// estimated with F(k) ≈ φ^k / √5, and corrected with fib_fast().
+///
+/// # Examples
+/// ```
+/// assert_eq!(fib_index(55), 10);
+/// assert_eq!(fib_index(100), 11);  // F(11) = 89, F(12) = 144
+/// ```
+#[allow(dead_code)]
+pub fn fib_index(n: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+    let phi = (1.0 + 5f64.sqrt()) / 2.0;
+    let mut k = ((n as f64 * 5f64.sqrt()).ln() / phi.ln())
+        .round()
+        .clamp(2.0, 93.0) as u64;
+    while fib_fast(k) > n as u128 {
+        k -= 1;
+    }
+    while fib_fast(k + 1) <= n as u128 {
+        k += 1;
+    }
+    k
+}
+
+/// Returns the Zeckendorf representation of n, largest first
+///
+/// The greedy choice of the largest Fibonacci number that fits always leaves
+/// a remainder smaller than the previous Fibonacci number, so no two of the
+/// terms are consecutive.  0 is the empty sum.
+///
+/// # Examples
+/// ```
+/// assert_eq!(zeckendorf(100), vec![89, 8, 3]);
+/// ```
+#[allow(dead_code)]
+pub fn zeckendorf(n: u64) -> Vec<u64> {
+    let mut terms = Vec::new();
+    let mut n = n;
+    while n > 0 {
+        let f = fib_fast(fib_index(n)) as u64;
+        terms.push(f);
+        n -= f;
+    }
+    terms
+}
+
+/// Returns F(0) + F(1) + ... + F(n)
+///
+/// The sum telescopes to F(n+2) - 1.
+///
+/// # Examples
+/// ```
+/// assert_eq!(fib_sum(10), 143);
+/// ```
+///
+/// # Panics
+///
+/// will panic if n > 184
+#[allow(dead_code)]
+pub fn fib_sum(n: u64) -> u128 {
+    fib_fast(n + 2) - 1
+}
+
+/// Returns F(0) + F(k) + F(2k) + ... of the terms with index up to n
+///
+/// If m = n / k, the sum of F(jk) for j = 0 to m is
+///
+///     (F((m+1)k) - (-1)^k F(mk) - F(k)) / (L(k) - (-1)^k - 1)
+///
+/// where L(k) = F(k-1) + F(k+1) is the kth Lucas number.  This comes from
+/// summing the geometric series in φ^k and ψ^k of Binet's formula.
+/// F((m+1)k) can overflow when the sum does not, so it is replaced with
+/// L(k) F(mk) - (-1)^k F((m-1)k), which leaves
+///
+///     F(mk) + (F(mk) + F((m-1)k) - F(k)) / L(k)        for odd k
+///     F(mk) + (F(mk) - F((m-1)k) - F(k)) / (L(k) - 2)  for even k
+///
+/// # Examples
+/// ```
+/// assert_eq!(fib_sum_every(10, 2), 0 + 1 + 3 + 8 + 21 + 55);
+/// ```
+///
+/// # Panics
+///
+/// will panic if k = 0 or n > 184
+#[allow(dead_code)]
+pub fn fib_sum_every(n: u64, k: u64) -> u128 {
+    assert!(k > 0, "k must be positive");
+    assert!(n <= 184, "the sum overflows a u128");
+    let m = n / k;
+    if m == 0 {
+        return 0;
+    }
+    let lucas = fib_fast(k - 1) + fib_fast(k + 1);
+    let last = fib_fast(m * k);
+    let before = fib_fast((m - 1) * k);
+    if k.is_multiple_of(2) {
+        last + (last - before - fib_fast(k)) / (lucas - 2)
+    } else {
+        last + (last + before - fib_fast(k)) / lucas
+    }
+}
+
+/// Returns the sum of the even Fibonacci numbers with index up to n
+///
+/// F(k) is even exactly when k is a multiple of 3, so this is fib_sum_every(n, 3),
+/// which simplifies to F(3m) + (F(3m) + F(3m-3) - 2) / 4.
+///
+/// # Examples
+/// ```
+/// assert_eq!(fib_sum_even(11), 2 + 8 + 34);
+/// ```
+///
+/// # Panics
+///
+/// will panic if n > 184
+pub fn fib_sum_even(n: u64) -> u128 {
+    fib_sum_every(n, 3)
+}
+
+/// Returns the sum of the odd Fibonacci numbers with index up to n
+///
+/// # Examples
+/// ```
+/// assert_eq!(fib_sum_odd(6), 1 + 1 + 3 + 5);
+/// ```
+///
+/// # Panics
+///
+/// will panic if n > 184
+#[allow(dead_code)]
+pub fn fib_sum_odd(n: u64) -> u128 {
+    fib_sum(n) - fib_sum_even(n)
+}
+
 // TODO: Create a generator based solution.
 // This will not require a struct to hold state
 // Generators are not in the stable release (as of 2018-01-07)
@@ -175,6 +529,7 @@ pub fn iter2() -> impl Iterator<Item = usize> {
 /// The iterator is just as fast at about < 20ns per number.
 /// The vector based solutions are also fast, but only useful if you need
 /// to scan the iterator sequence multiple times.
+/// Fast doubling only wins for large n, where the loop would take n steps.
 #[allow(dead_code)]
 pub fn benchmark() {
     println!("Recursive solution for specific fibonacci number");
@@ -213,4 +568,119 @@ pub fn benchmark() {
         println!("fib #{} = {} in {:?}", i, f, start.elapsed());
         start = std::time::Instant::now();
     }
+    println!("Loop and fast doubling solutions for F(90)");
+    let start = std::time::Instant::now();
+    println!("fib #90 = {} in {:?}", fibonacci(90), start.elapsed());
+    let start = std::time::Instant::now();
+    println!("fib #90 = {} in {:?}", fib_fast(90), start.elapsed());
+    println!("Fast doubling solution for F(10^18) mod 10^9+7");
+    let start = std::time::Instant::now();
+    let f = fib_mod(1_000_000_000_000_000_000, 1_000_000_007);
+    println!("fib #10^18 = {} (mod 10^9+7) in {:?}", f, start.elapsed());
+    println!("Loop and fast doubling solutions for the digits in F(100,000)");
+    let start = std::time::Instant::now();
+    let f = fibonacci_big(100_000).to_string().len();
+    println!("fib #100000 has {} digits in {:?}", f, start.elapsed());
+    let start = std::time::Instant::now();
+    let f = fib_fast_big(100_000).to_string().len();
+    println!("fib #100000 has {} digits in {:?}", f, start.elapsed());
+    println!("Pisano periods");
+    for &m in [10, 1000, 1_000_000_007].iter() {
+        let start = std::time::Instant::now();
+        println!("π({}) = {} in {:?}", m, pisano_period(m), start.elapsed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn fibonacci_big_test() {
+        for n in 0..94 {
+            assert_eq!(fibonacci_big(n).to_u64(), Some(fibonacci(n) as u64));
+        }
+        // Euler problem 25, the first term with 1000 digits
+        assert_eq!(fibonacci_big(4781).to_string().len(), 999);
+        assert_eq!(fibonacci_big(4782).to_string().len(), 1000);
+    }
+    #[test]
+    pub fn fib_fast_test() {
+        let mut x: (u128, u128) = (0, 1);
+        for n in 0..=186 {
+            assert_eq!(fib_fast(n), x.0);
+            x = (x.1, x.0.wrapping_add(x.1));
+        }
+        for n in 0..=1000 {
+            let big = fibonacci_big(n as usize);
+            assert_eq!(fib_fast_big(n), big);
+            for &m in [1, 2, 10, 1_000_000_007, u64::MAX].iter() {
+                let expected = (&big % &BigUint::from(m)).to_u64().unwrap();
+                assert_eq!(fib_mod(n, m), expected);
+            }
+        }
+    }
+    #[test]
+    pub fn pisano_period_test() {
+        for m in 1..=2000u64 {
+            // count until the pair (0, 1) repeats
+            let mut x = (1 % m, 1 % m);
+            let mut period = 1;
+            while x != (0, 1 % m) {
+                x = (x.1, (x.0 + x.1) % m);
+                period += 1;
+            }
+            assert_eq!(pisano_period(m), period, "m = {}", m);
+        }
+        assert_eq!(pisano_period(1_000_000_007), 2_000_000_016);
+        // F(n) mod m only depends on n mod π(m)
+        let m = 999_983 * 1_000_003;
+        let p = pisano_period(m);
+        assert_eq!(fib_mod(p + 12345, m), fib_mod(12345, m));
+    }
+    #[test]
+    pub fn fib_index_test() {
+        let fibs: Vec<u64> = (0..=93).map(|k| fib_fast(k) as u64).collect();
+        for n in 0..=10_000u64 {
+            assert_eq!(is_fibonacci(n), fibs.contains(&n), "n = {}", n);
+            let k = fib_index(n);
+            assert!(fibs[k as usize] <= n && n < fibs[k as usize + 1]);
+        }
+        for (k, &f) in fibs.iter().enumerate().skip(3) {
+            assert!(is_fibonacci(f));
+            assert!(!is_fibonacci(f + 1) || f < 3);
+            assert_eq!(fib_index(f), k as u64);
+            assert_eq!(fib_index(f - 1), k as u64 - 1);
+        }
+        assert_eq!(fib_index(u64::MAX), 93);
+    }
+    #[test]
+    pub fn zeckendorf_test() {
+        for n in 0..=10_000u64 {
+            let z = zeckendorf(n);
+            assert_eq!(z.iter().sum::<u64>(), n);
+            let indices: Vec<u64> = z.iter().map(|&f| fib_index(f)).collect();
+            assert!(indices.windows(2).all(|w| w[0] >= w[1] + 2));
+            assert!(indices.iter().all(|&k| k >= 2));
+        }
+        assert_eq!(zeckendorf(0), vec![]);
+        assert_eq!(zeckendorf(u64::MAX).iter().sum::<u64>(), u64::MAX);
+    }
+    #[test]
+    pub fn fib_sum_test() {
+        let fibs: Vec<u128> = (0..=186).map(fib_fast).collect();
+        for n in 0..=184 {
+            let total: u128 = fibs[..=n].iter().sum();
+            assert_eq!(fib_sum(n as u64), total);
+            let even: u128 = fibs[..=n].iter().filter(|&f| f % 2 == 0).sum();
+            assert_eq!(fib_sum_even(n as u64), even);
+            assert_eq!(fib_sum_odd(n as u64), total - even);
+        }
+        for k in 1..=190 {
+            for n in 0..=184 {
+                let every: u128 = fibs[..=n].iter().step_by(k).sum();
+                assert_eq!(fib_sum_every(n as u64, k as u64), every, "n {} k {}", n, k);
+            }
+        }
+    }
 }
@@ -0,0 +1,298 @@
+//! Figurate Numbers
+//!
+//! [Figurate numbers] count the stones in a regular shape.
+//!
+//! * polygonal(s, n): the nth s-gonal number, P(s, n) = ((s-2)n^2 - (s-4)n) / 2,
+//!   the triangle (s = 3), square (4), pentagonal (5), hexagonal (6), ... numbers
+//! * centered_polygonal(s, n): an s-gon of dots around a central dot, s n(n-1)/2 + 1
+//! * pyramidal(s, n): the sum of the first n s-gonal numbers, stacked in layers
+//! * polytopic_number(r, n): the r dimensional simplex numbers, C(n+r-1, r)
+//!
+//! The inverse tests solve P(s, n) = x for n with the quadratic formula, using
+//! an integer square root, so there is no floating point rounding to worry about.
+//!
+//! [Figurate numbers]: https://en.wikipedia.org/wiki/Figurate_number
+
+use super::combinatorics;
+use num_integer::Roots;
+use std::convert::TryFrom;
+use std::ops::Add;
+
+/// Returns the nth s-gonal number, P(s, n) = ((s-2)n^2 - (s-4)n) / 2
+///
+/// P(s, 1) = 1 and P(s, 2) = s for every s.
+///
+/// # Examples
+/// ```
+/// assert_eq!(polygonal(3, 4), 10);  // triangle
+/// assert_eq!(polygonal(4, 4), 16);  // square
+/// assert_eq!(polygonal(5, 4), 22);  // pentagonal
+/// ```
+///
+/// # Panics
+///
+/// will panic if s < 3, or if the result overflows
+#[allow(dead_code)]
+pub fn polygonal(s: u64, n: u64) -> u64 {
+    assert!(s >= 3, "a polygon has at least 3 sides");
+    let (s, n) = (s as u128, n as u128);
+    // (s-2)n^2 - (s-4)n = n((s-2)(n-1) + 2), which has no negative term
+    let twice = n * ((s - 2) * n.saturating_sub(1) + 2);
+    u64::try_from(twice / 2).expect("polygonal number overflow")
+}
+
+/// Returns n if x is the nth s-gonal number, P(s, n) = x, otherwise None
+///
+/// Solves (s-2)n^2 - (s-4)n - 2x = 0, so
+///
+///     n = ((s-4) + √((s-4)^2 + 8(s-2)x)) / (2(s-2))
+///
+/// which must have an exact square root and an exact division.
+///
+/// # Examples
+/// ```
+/// assert_eq!(polygonal_index(5, 22), Some(4));
+/// assert_eq!(polygonal_index(5, 23), None);
+/// assert_eq!(polygonal_index(3, 0), Some(0));
+/// ```
+///
+/// # Panics
+///
+/// will panic if s < 3
+#[allow(dead_code)]
+pub fn polygonal_index(s: u64, x: u64) -> Option<u64> {
+    assert!(s >= 3, "a polygon has at least 3 sides");
+    if x == 0 {
+        // the other root of the quadratic, which is 0 for every s
+        return Some(0);
+    }
+    let (s, x) = (s as i128, x as i128);
+    let discriminant = ((s - 4) * (s - 4) + 8 * (s - 2) * x) as u128;
+    let root = discriminant.sqrt();
+    if root * root != discriminant {
+        return None;
+    }
+    let numerator = s - 4 + root as i128;
+    let denominator = 2 * (s - 2);
+    if numerator % denominator == 0 {
+        Some((numerator / denominator) as u64)
+    } else {
+        None
+    }
+}
+
+/// Returns true if x is a triangle number, n(n+1)/2
+///
+/// # Examples
+/// ```
+/// assert!(is_triangular(55));
+/// ```
+#[allow(dead_code)]
+pub fn is_triangular(x: u64) -> bool {
+    polygonal_index(3, x).is_some()
+}
+
+/// Returns true if x is a pentagonal number, n(3n-1)/2
+///
+/// # Examples
+/// ```
+/// assert!(is_pentagonal(5482660));
+/// ```
+#[allow(dead_code)]
+pub fn is_pentagonal(x: u64) -> bool {
+    polygonal_index(5, x).is_some()
+}
+
+/// Returns true if x is a hexagonal number, n(2n-1)
+///
+/// Every hexagonal number is also a triangle number, H(n) = T(2n-1).
+///
+/// # Examples
+/// ```
+/// assert!(is_hexagonal(40755));
+/// ```
+#[allow(dead_code)]
+pub fn is_hexagonal(x: u64) -> bool {
+    polygonal_index(6, x).is_some()
+}
+
+/// Returns the nth centered s-gonal number, s n(n-1)/2 + 1
+///
+/// A central dot, surrounded by s-gons of 1, 2, ... n-1 dots a side.
+///
+/// # Examples
+/// ```
+/// assert_eq!(centered_polygonal(6, 3), 19);  // centered hexagonal: 1, 7, 19, 37
+/// ```
+///
+/// # Panics
+///
+/// will panic if n is zero, or if the result overflows
+#[allow(dead_code)]
+pub fn centered_polygonal(s: u64, n: u64) -> u64 {
+    assert!(n > 0, "n must be positive");
+    let (s, n) = (s as u128, n as u128);
+    u64::try_from(s * n * (n - 1) / 2 + 1).expect("centered polygonal number overflow")
+}
+
+/// Returns the nth s-gonal pyramidal number, P(s, 1) + P(s, 2) + ... + P(s, n)
+///
+/// In closed form, n(n+1)((s-2)n - (s-5)) / 6.  s = 3 gives the tetrahedral
+/// numbers and s = 4 the square pyramidal numbers.
+///
+/// # Examples
+/// ```
+/// assert_eq!(pyramidal(4, 3), 14);  // 1 + 4 + 9
+/// ```
+///
+/// # Panics
+///
+/// will panic if s < 3, or if the result overflows
+#[allow(dead_code)]
+pub fn pyramidal(s: u64, n: u64) -> u64 {
+    assert!(s >= 3, "a polygon has at least 3 sides");
+    let (s, n) = (s as u128, n as u128);
+    // (s-2)n - (s-5) = (s-2)(n-1) + 3
+    let sixfold = n
+        .checked_mul(n + 1)
+        .and_then(|x| x.checked_mul((s - 2) * n.saturating_sub(1) + 3))
+        .expect("pyramidal number overflow");
+    u64::try_from(sixfold / 6).expect("pyramidal number overflow")
+}
+
+/// Returns the nth [polytopic_number] of dimension r
+///
+/// * When r = 1, these are the linear numbers (1,2,3,4..) P1(n) = n
+/// * When r = 2, these are the [Triangle numbers] (1,3,6,10,...) P2(n) = n(n+1)/2
+/// * When r = 3, these are the [Tetrahedral numbers] (1,4,10,20,..) P3(n) = n(n+1)(n+2)/(3*2)
+///
+/// The triangle number n is the sum of the first n linear numbers, the tetrahedral number
+/// n is the sum of the first n triangle numbers, etc.
+///
+/// In general, the r-topic numbers (r-simplex numbers) are:
+///   Pr(n) = n(n+1)(n+2)...(n+r-1)/r! = C(n+r-1, r)
+///
+/// The binomial coefficient is computed without overflowing before the result does
+/// (see combinatorics::binomial).
+///
+/// # Examples
+///
+/// ```
+/// // The 4th triangle number (r=2) is 10
+/// assert_eq!(polytopic_number(2, 4), 10)
+/// ```
+///
+/// # Panics
+///
+/// will panic if r or n is zero, or if the result overflows
+///
+/// [polytopic_number]: https://en.wikipedia.org/wiki/Figurate_number
+/// [Triangle numbers]: https://en.wikipedia.org/wiki/Triangular_number
+/// [Tetrahedral numbers]: https://en.wikipedia.org/wiki/Tetrahedral_number
+#[allow(dead_code)]
+pub fn polytopic_number(r: usize, n: usize) -> usize {
+    assert!(r > 0 && n > 0, "r and n must be positive");
+    combinatorics::binomial((n + r - 1) as u64, r as u64) as usize
+}
+
+/// Returns a table of the polytopic numbers, table[r-1][n-1] = polytopic_number(r, n)
+///
+/// The table has dimensions 1 to r and numbers 1 to n.  Each number is the sum
+/// of the first n numbers of the dimension below, so each entry is one
+/// addition, the one to the left plus the one above:
+///
+/// ```
+/// 1:   1 2  3  4  5  6
+/// 2:   1 3  6 10 15 21
+/// 3:   1 4 10 20 35 56
+/// ```
+///
+/// The numbers can get very large, so T may be a BigUint.
+///
+/// # Examples
+/// ```
+/// let p: Vec<Vec<u64>> = polytopic_table(3, 6);
+/// assert_eq!(p[2][5], 56);
+/// ```
+pub fn polytopic_table<T>(r: usize, n: usize) -> Vec<Vec<T>>
+where
+    T: Clone + From<u8> + Add<Output = T>,
+{
+    let mut p: Vec<Vec<T>> = vec![vec![T::from(1); n]; r];
+    for i in 0..r {
+        for j in 1..n {
+            p[i][j] = if i == 0 {
+                p[i][j - 1].clone() + T::from(1)
+            } else {
+                p[i][j - 1].clone() + p[i - 1][j].clone()
+            };
+        }
+    }
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn polytopic_number_test() {
+        assert_eq!(polytopic_number(2, 4), 10);
+        assert_eq!(polytopic_number(1, 7), 7);
+        assert_eq!(polytopic_number(3, 4), 20);
+        // n(n+1)...(n+r-1) overflows a u64 for these, but the result does not
+        assert_eq!(polytopic_number(12, 100), 3_943_664_897_925_675);
+        assert_eq!(polytopic_number(3, 3_000_000), 4_500_004_500_001_000_000);
+        let table: Vec<Vec<u64>> = polytopic_table(9, 40);
+        for r in 1..=9 {
+            for n in 1..=40 {
+                assert_eq!(table[r - 1][n - 1], polytopic_number(r, n) as u64);
+            }
+        }
+    }
+    #[test]
+    pub fn polygonal_test() {
+        for s in 3..=20 {
+            let mut sum = 0;
+            for n in 0..=1000 {
+                let p = polygonal(s, n);
+                // the layers of the polygon grow by s-2 more each time
+                let expected = if n == 0 {
+                    0
+                } else {
+                    (0..n).map(|k| 1 + (s - 2) * k).sum()
+                };
+                assert_eq!(p, expected);
+                assert_eq!(polygonal_index(s, p), Some(n));
+                assert_eq!(
+                    polygonal_index(s, p + 1),
+                    if n == 0 { Some(1) } else { None }
+                );
+                sum += p;
+                if n > 0 {
+                    assert_eq!(pyramidal(s, n), sum);
+                }
+            }
+        }
+        assert_eq!(pyramidal(3, 10), polytopic_number(3, 10) as u64);
+        // Euler problem 45, the next triangle number after 40755 that is pentagonal and hexagonal
+        let next = (144..)
+            .map(|n| polygonal(6, n))
+            .find(|&h| is_pentagonal(h) && is_triangular(h));
+        assert_eq!(next, Some(1_533_776_805));
+        assert!(is_hexagonal(1_533_776_805));
+        // the largest triangle number in a u64
+        let n = 6_074_000_999;
+        assert_eq!(polygonal_index(3, polygonal(3, n)), Some(n));
+        assert_eq!(polygonal_index(3, u64::MAX), None);
+    }
+    #[test]
+    pub fn centered_polygonal_test() {
+        let c: Vec<u64> = (1..=6).map(|n| centered_polygonal(6, n)).collect();
+        assert_eq!(c, vec![1, 7, 19, 37, 61, 91]);
+        // centered square numbers are the sum of consecutive squares
+        for n in 1..100 {
+            assert_eq!(centered_polygonal(4, n), n * n + (n - 1) * (n - 1));
+        }
+    }
+}
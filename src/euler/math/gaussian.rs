@@ -0,0 +1,593 @@
+//! Gaussian Integers
+//!
+//! A [Gaussian integer] is a complex number a + bi with integer a and b.  They
+//! have unique factorization, like the integers, up to the four units ±1, ±i.
+//!
+//! * The norm N(a + bi) = a^2 + b^2 is multiplicative, N(zw) = N(z) N(w), so
+//!   factoring z in ℤ[i] starts with factoring N(z) in ℤ.
+//! * Division rounds the exact quotient to the nearest Gaussian integer, which
+//!   leaves a remainder with at most half the norm of the divisor.  So there is
+//!   a Euclidean algorithm, and a gcd.
+//! * Every Gaussian prime divides exactly one rational prime p:
+//!   - 2 = -i (1 + i)^2 ramifies
+//!   - p = 1 (mod 4) splits as (a + bi)(a - bi), where p = a^2 + b^2
+//!   - q = 3 (mod 4) stays prime
+//!
+//! The components are stored as i64, and products are computed in i128, so any
+//! Gaussian integer with a norm that fits in a u64 can be multiplied by another.
+//! i64::MIN is not a valid component, so that negating never overflows, and
+//! the norm and the products in div_rem always fit in an i128.
+//!
+//! The split of p = 1 (mod 4) is found with [Cornacchia's algorithm].
+//!
+//! [Gaussian integer]: https://en.wikipedia.org/wiki/Gaussian_integer
+//! [Cornacchia's algorithm]: https://en.wikipedia.org/wiki/Cornacchia%27s_algorithm
+
+use super::{modular, primality};
+use num_integer::{Integer, Roots};
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// A Gaussian integer re + im i, where re and im are greater than i64::MIN
+///
+/// # Examples
+/// ```
+/// let z = GaussianInt::new(2, 1);
+/// assert_eq!(z * z.conj(), GaussianInt::from(5));
+/// assert_eq!((z * z).to_string(), "3+4i");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GaussianInt {
+    pub re: i64,
+    pub im: i64,
+}
+
+impl GaussianInt {
+    /// 0
+    pub const ZERO: GaussianInt = GaussianInt { re: 0, im: 0 };
+    /// 1
+    pub const ONE: GaussianInt = GaussianInt { re: 1, im: 0 };
+    /// i, the square root of -1
+    #[allow(dead_code)]
+    pub const I: GaussianInt = GaussianInt { re: 0, im: 1 };
+
+    /// Returns re + im i
+    ///
+    /// # Panics
+    ///
+    /// will panic if re or im is i64::MIN
+    pub fn new(re: i64, im: i64) -> GaussianInt {
+        assert!(
+            re != i64::MIN && im != i64::MIN,
+            "i64::MIN is not a valid component"
+        );
+        GaussianInt { re, im }
+    }
+
+    /// Returns the Gaussian integer re + im i, computed in i128
+    ///
+    /// # Panics
+    ///
+    /// will panic if either component does not fit in an i64 (or is i64::MIN)
+    fn from_wide(re: i128, im: i128) -> GaussianInt {
+        let component = |x: i128| {
+            i64::try_from(x)
+                .ok()
+                .filter(|&x| x != i64::MIN)
+                .expect("Gaussian integer overflow")
+        };
+        GaussianInt {
+            re: component(re),
+            im: component(im),
+        }
+    }
+
+    /// Returns the complex conjugate re - im i
+    pub fn conj(self) -> GaussianInt {
+        GaussianInt::new(self.re, -self.im)
+    }
+
+    /// Returns the norm re^2 + im^2, which is |z|^2
+    pub fn norm(self) -> u128 {
+        let (a, b) = (self.re as i128, self.im as i128);
+        (a * a + b * b) as u128
+    }
+
+    /// Returns true if self is one of the units ±1, ±i
+    #[allow(dead_code)]
+    pub fn is_unit(self) -> bool {
+        self.norm() == 1
+    }
+
+    /// Returns self^e
+    ///
+    /// # Panics
+    ///
+    /// will panic if the result overflows
+    pub fn pow(self, e: u32) -> GaussianInt {
+        (0..e).fold(GaussianInt::ONE, |w, _| w * self)
+    }
+
+    /// Returns the associate of self (self times a unit) with re > 0 and im >= 0
+    ///
+    /// Each non-zero Gaussian integer has four associates, one in each quadrant,
+    /// so this picks a standard one.  Zero is returned unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// assert_eq!(GaussianInt::new(-3, 2).normalize(), GaussianInt::new(2, 3));
+    /// ```
+    pub fn normalize(self) -> GaussianInt {
+        let mut z = self;
+        if z == GaussianInt::ZERO {
+            return z;
+        }
+        // multiply by i, which rotates a quarter turn, until it is in the first quadrant
+        while z.re <= 0 || z.im < 0 {
+            z = GaussianInt::new(-z.im, z.re);
+        }
+        z
+    }
+
+    /// Returns (q, r) with self = q other + r and N(r) <= N(other) / 2
+    ///
+    /// q is the exact quotient self / other = self conj(other) / N(other),
+    /// with each component rounded to the nearest integer.
+    ///
+    /// # Examples
+    /// ```
+    /// let (q, r) = GaussianInt::new(7, 5).div_rem(GaussianInt::new(2, 1));
+    /// assert_eq!((q, r), (GaussianInt::new(4, 1), GaussianInt::new(0, -1)));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// will panic if other is zero, or the quotient overflows
+    pub fn div_rem(self, other: GaussianInt) -> (GaussianInt, GaussianInt) {
+        assert!(other != GaussianInt::ZERO, "division by zero");
+        let n = other.norm() as i128;
+        let (a, b) = (self.re as i128, self.im as i128);
+        let (c, d) = (other.re as i128, other.im as i128);
+        // round x/n to the nearest integer, with halves rounded up.  x can be
+        // close to 2^127, so the remainder is compared instead of adding n/2
+        let round = |x: i128| {
+            let (q, r) = Integer::div_mod_floor(&x, &n);
+            if r >= n - r {
+                q + 1
+            } else {
+                q
+            }
+        };
+        let (qa, qb) = (round(a * c + b * d), round(b * c - a * d));
+        // q other can overflow an i64 even when the remainder does not
+        let r = GaussianInt::from_wide(a - (qa * c - qb * d), b - (qa * d + qb * c));
+        (GaussianInt::from_wide(qa, qb), r)
+    }
+
+    /// Returns the greatest common divisor of self and other, normalized
+    ///
+    /// Every common divisor divides the gcd.  It is only defined up to a unit,
+    /// so the associate in the first quadrant is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// // both are multiples of 1+2i (norm 5) and 3-2i (norm 13)
+    /// assert_eq!(GaussianInt::from(65).gcd(GaussianInt::new(-1, 18)), GaussianInt::new(7, 4));
+    /// ```
+    #[allow(dead_code)]
+    pub fn gcd(self, other: GaussianInt) -> GaussianInt {
+        let (mut a, mut b) = (self, other);
+        while b != GaussianInt::ZERO {
+            let r = a % b;
+            a = b;
+            b = r;
+        }
+        a.normalize()
+    }
+
+    /// Returns true if self is a Gaussian prime
+    ///
+    /// Either the norm is a rational prime (2 or p = 1 (mod 4)), or self is an
+    /// associate of a rational prime q = 3 (mod 4).
+    ///
+    /// # Examples
+    /// ```
+    /// assert!(GaussianInt::new(2, 1).is_prime());
+    /// assert!(GaussianInt::new(0, 3).is_prime());
+    /// assert!(!GaussianInt::from(5).is_prime());
+    /// ```
+    #[allow(dead_code)]
+    pub fn is_prime(self) -> bool {
+        let z = self.normalize();
+        if z.im == 0 {
+            let q = z.re as u64;
+            q % 4 == 3 && primality::is_prime(q)
+        } else {
+            u64::try_from(z.norm()).is_ok_and(primality::is_prime)
+        }
+    }
+
+    /// Returns the Gaussian primes that divide the rational prime p, normalized
+    ///
+    /// That is 1 + i for 2, a + bi and b + ai (the associate of a - bi) for
+    /// p = a^2 + b^2 = 1 (mod 4), and p itself for p = 3 (mod 4).
+    ///
+    /// # Examples
+    /// ```
+    /// assert_eq!(GaussianInt::split_prime(13), vec![GaussianInt::new(3, 2), GaussianInt::new(2, 3)]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// will panic if p = 3 (mod 4) and p > i64::MAX, since p is then not a GaussianInt
+    pub fn split_prime(p: u64) -> Vec<GaussianInt> {
+        match prime_two_squares(p) {
+            Some(_) if p == 2 => vec![GaussianInt::new(1, 1)],
+            Some((a, b)) => vec![
+                GaussianInt::new(a as i64, b as i64),
+                GaussianInt::new(b as i64, a as i64),
+            ],
+            None => vec![GaussianInt::from(
+                i64::try_from(p).expect("p does not fit in a GaussianInt"),
+            )],
+        }
+    }
+
+    /// Returns the factorization of self as a unit and a list of (prime, exponent)
+    ///
+    /// self = unit * p1^e1 * p2^e2 * ..., with the primes normalized.  The
+    /// norm is factored over the rational primes, then each rational prime is
+    /// split into its Gaussian primes, which are divided out of self.
+    ///
+    /// # Examples
+    /// ```
+    /// // 10 = -(1+i)^2 (2+i) (1+2i)
+    /// let (unit, factors) = GaussianInt::from(10).factor();
+    /// assert_eq!(unit, -GaussianInt::ONE);
+    /// assert_eq!(factors, vec![(GaussianInt::new(1, 1), 2), (GaussianInt::new(2, 1), 1), (GaussianInt::new(1, 2), 1)]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// will panic if self is zero, or its norm does not fit in a u64
+    #[allow(dead_code)]
+    pub fn factor(self) -> (GaussianInt, Vec<(GaussianInt, usize)>) {
+        assert!(self != GaussianInt::ZERO, "zero has no factorization");
+        let norm = u64::try_from(self.norm()).expect("the norm must fit in a u64");
+        let mut rest = self;
+        let mut factors = Vec::new();
+        for (p, _) in primality::factor(norm) {
+            for prime in GaussianInt::split_prime(p) {
+                let mut e = 0;
+                loop {
+                    let (q, r) = rest.div_rem(prime);
+                    if r != GaussianInt::ZERO {
+                        break;
+                    }
+                    rest = q;
+                    e += 1;
+                }
+                if e > 0 {
+                    factors.push((prime, e));
+                }
+            }
+        }
+        (rest, factors)
+    }
+
+    /// Returns every Gaussian integer with norm n, with re > 0 and im >= 0
+    ///
+    /// These are the solutions of a^2 + b^2 = n, up to the units.  Each one is
+    /// a product of a Gaussian integer of norm p^e for each prime power in n:
+    /// (1+i)^e for 2, π^k conj(π)^(e-k) for p = ππ' = 1 (mod 4), and q^(e/2)
+    /// for q = 3 (mod 4), which must have e even.  So there are r2(n)/4 of them.
+    ///
+    /// # Examples
+    /// ```
+    /// let z: Vec<GaussianInt> = GaussianInt::with_norm(25);
+    /// assert_eq!(z, vec![GaussianInt::new(3, 4), GaussianInt::new(4, 3), GaussianInt::new(5, 0)]);
+    /// ```
+    pub fn with_norm(n: u64) -> Vec<GaussianInt> {
+        if n == 0 {
+            return vec![GaussianInt::ZERO];
+        }
+        let mut products = vec![GaussianInt::ONE];
+        for (p, e) in primality::factor(n) {
+            let primes = GaussianInt::split_prime(p);
+            let choices: Vec<GaussianInt> = match primes.len() {
+                2 => {
+                    let (a, b) = (primes[0], primes[0].conj());
+                    (0..=e as u32)
+                        .map(|k| a.pow(k) * b.pow(e as u32 - k))
+                        .collect()
+                }
+                _ if p == 2 => vec![primes[0].pow(e as u32)],
+                _ if e % 2 == 0 => vec![primes[0].pow(e as u32 / 2)],
+                _ => return Vec::new(),
+            };
+            products = products
+                .iter()
+                .flat_map(|&z| choices.iter().map(move |&w| z * w))
+                .collect();
+        }
+        let mut z: Vec<GaussianInt> = products.into_iter().map(|z| z.normalize()).collect();
+        z.sort_unstable_by_key(|z| (z.re, z.im));
+        z
+    }
+}
+
+impl From<i64> for GaussianInt {
+    fn from(n: i64) -> GaussianInt {
+        GaussianInt::new(n, 0)
+    }
+}
+
+impl fmt::Display for GaussianInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.re, self.im) {
+            (re, 0) => write!(f, "{}", re),
+            (0, im) => write!(f, "{}i", im),
+            (re, im) if im < 0 => write!(f, "{}{}i", re, im),
+            (re, im) => write!(f, "{}+{}i", re, im),
+        }
+    }
+}
+
+impl Add for GaussianInt {
+    type Output = GaussianInt;
+    fn add(self, rhs: GaussianInt) -> GaussianInt {
+        GaussianInt::from_wide(
+            self.re as i128 + rhs.re as i128,
+            self.im as i128 + rhs.im as i128,
+        )
+    }
+}
+
+impl Sub for GaussianInt {
+    type Output = GaussianInt;
+    fn sub(self, rhs: GaussianInt) -> GaussianInt {
+        self + (-rhs)
+    }
+}
+
+impl Neg for GaussianInt {
+    type Output = GaussianInt;
+    fn neg(self) -> GaussianInt {
+        GaussianInt::new(-self.re, -self.im)
+    }
+}
+
+impl Mul for GaussianInt {
+    type Output = GaussianInt;
+    fn mul(self, rhs: GaussianInt) -> GaussianInt {
+        let (a, b) = (self.re as i128, self.im as i128);
+        let (c, d) = (rhs.re as i128, rhs.im as i128);
+        GaussianInt::from_wide(a * c - b * d, a * d + b * c)
+    }
+}
+
+/// The rounded quotient, see div_rem
+impl Div for GaussianInt {
+    type Output = GaussianInt;
+    fn div(self, rhs: GaussianInt) -> GaussianInt {
+        self.div_rem(rhs).0
+    }
+}
+
+/// The remainder of the rounded quotient, see div_rem
+impl Rem for GaussianInt {
+    type Output = GaussianInt;
+    fn rem(self, rhs: GaussianInt) -> GaussianInt {
+        self.div_rem(rhs).1
+    }
+}
+
+/// Returns x with x^2 = -1 (mod p), for a prime p = 1 (mod 4)
+///
+/// If c is a quadratic non-residue then c^((p-1)/2) = -1, so c^((p-1)/4) is
+/// a square root of -1.  Half of the c are non-residues, so only a few are tried.
+fn sqrt_minus_one(p: u64) -> u64 {
+    (2..p)
+        .map(|c| modular::pow_mod(c, (p - 1) / 4, p))
+        .find(|&x| modular::mul_mod(x, x, p) == p - 1)
+        .expect("p must be a prime = 1 (mod 4)")
+}
+
+/// Returns (a, b) with a >= b and a^2 + b^2 = p, for a prime p
+///
+/// Cornacchia's algorithm: starting from x^2 = -1 (mod p), run the Euclidean
+/// algorithm on p and x until the remainder is at most √p.  That remainder is
+/// a, and b = √(p - a^2).  Returns None if p = 3 (mod 4).
+///
+/// # Examples
+/// ```
+/// assert_eq!(prime_two_squares(13), Some((3, 2)));
+/// assert_eq!(prime_two_squares(7), None);
+/// ```
+pub fn prime_two_squares(p: u64) -> Option<(u64, u64)> {
+    if p == 2 {
+        return Some((1, 1));
+    }
+    if p % 4 != 1 {
+        return None;
+    }
+    let limit = p.sqrt();
+    let (mut a, mut b) = (p, sqrt_minus_one(p));
+    while b > limit {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    let c = (p - b * b).sqrt();
+    Some((b.max(c), b.min(c)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all(limit: i64) -> impl Iterator<Item = GaussianInt> {
+        (-limit..=limit)
+            .flat_map(move |re| (-limit..=limit).map(move |im| GaussianInt::new(re, im)))
+    }
+
+    #[test]
+    pub fn arithmetic_test() {
+        let z = GaussianInt::new(3, -2);
+        let w = GaussianInt::new(-1, 4);
+        assert_eq!(z + w, GaussianInt::new(2, 2));
+        assert_eq!(z - w, GaussianInt::new(4, -6));
+        assert_eq!(z * w, GaussianInt::new(5, 14));
+        assert_eq!(GaussianInt::I * GaussianInt::I, -GaussianInt::ONE);
+        assert_eq!((z * w).norm(), z.norm() * w.norm());
+        assert_eq!(GaussianInt::new(1, 1).pow(4), GaussianInt::from(-4));
+        let s: Vec<String> = [z, w, GaussianInt::I, GaussianInt::from(-7)]
+            .iter()
+            .map(|z| z.to_string())
+            .collect();
+        assert_eq!(s, vec!["3-2i", "-1+4i", "1i", "-7"]);
+        for z in all(6).filter(|&z| z != GaussianInt::ZERO) {
+            let n = z.normalize();
+            assert!(n.re > 0 && n.im >= 0, "{}", z);
+            assert!((n / z).is_unit() && n % z == GaussianInt::ZERO);
+        }
+    }
+    #[test]
+    pub fn div_rem_test() {
+        for a in all(12) {
+            for b in all(5).filter(|&b| b != GaussianInt::ZERO) {
+                let (q, r) = a.div_rem(b);
+                assert_eq!(q * b + r, a);
+                assert!(2 * r.norm() <= b.norm(), "{} / {}", a, b);
+            }
+        }
+        let (q, r) = GaussianInt::new(7, 5).div_rem(GaussianInt::new(2, 1));
+        assert_eq!((q, r), (GaussianInt::new(4, 1), GaussianInt::new(0, -1)));
+        // the largest components, where a conj(b) is close to 2^127
+        let big = [
+            GaussianInt::new(i64::MAX, i64::MAX),
+            GaussianInt::new(-i64::MAX, i64::MAX - 1),
+            GaussianInt::new(i64::MAX, -3),
+            GaussianInt::new(7, -i64::MAX),
+        ];
+        for &a in big.iter() {
+            for &b in big.iter() {
+                let (q, r) = a.div_rem(b);
+                // q b can overflow an i64, so it is checked in i128
+                let (qa, qb) = (q.re as i128, q.im as i128);
+                let (c, d) = (b.re as i128, b.im as i128);
+                assert_eq!(qa * c - qb * d + r.re as i128, a.re as i128);
+                assert_eq!(qa * d + qb * c + r.im as i128, a.im as i128);
+                assert!(2 * r.norm() <= b.norm(), "{} / {}", a, b);
+            }
+        }
+        let m = i64::MAX as u128;
+        assert_eq!(big[0].norm(), 2 * m * m);
+        // exact division
+        let z = GaussianInt::new(123, -45);
+        let w = GaussianInt::new(-67, 89);
+        assert_eq!((z * w) / w, z);
+        assert_eq!((z * w) % w, GaussianInt::ZERO);
+    }
+    #[test]
+    #[should_panic(expected = "i64::MIN is not a valid component")]
+    pub fn min_component_test() {
+        GaussianInt::new(0, i64::MIN);
+    }
+    #[test]
+    #[should_panic(expected = "p does not fit in a GaussianInt")]
+    pub fn split_prime_overflow_test() {
+        // the largest prime = 3 (mod 4) below 2^64
+        let p = u64::MAX - 188;
+        assert!(primality::is_prime(p) && p % 4 == 3);
+        GaussianInt::split_prime(p);
+    }
+    #[test]
+    pub fn gcd_test() {
+        let g = GaussianInt::from(65).gcd(GaussianInt::new(-1, 18));
+        assert_eq!(g, GaussianInt::new(7, 4));
+        for a in all(8) {
+            for b in all(8) {
+                let g = a.gcd(b);
+                if a == GaussianInt::ZERO && b == GaussianInt::ZERO {
+                    assert_eq!(g, GaussianInt::ZERO);
+                    continue;
+                }
+                assert_eq!(a % g, GaussianInt::ZERO);
+                assert_eq!(b % g, GaussianInt::ZERO);
+                // a/g and b/g have no common prime factor
+                assert_eq!((a / g).gcd(b / g), GaussianInt::ONE, "{} {}", a, b);
+            }
+        }
+    }
+    #[test]
+    pub fn factor_test() {
+        let (unit, factors) = GaussianInt::from(10).factor();
+        assert_eq!(unit, -GaussianInt::ONE);
+        let expected = [
+            (GaussianInt::new(1, 1), 2),
+            (GaussianInt::new(2, 1), 1),
+            (GaussianInt::new(1, 2), 1),
+        ];
+        assert_eq!(factors, expected);
+        assert_eq!(
+            GaussianInt::split_prime(13),
+            vec![GaussianInt::new(3, 2), GaussianInt::new(2, 3)]
+        );
+        for z in all(30).filter(|&z| z != GaussianInt::ZERO) {
+            let (unit, factors) = z.factor();
+            assert!(unit.is_unit());
+            let product = factors
+                .iter()
+                .fold(unit, |p, &(prime, e)| p * prime.pow(e as u32));
+            assert_eq!(product, z);
+            assert!(factors
+                .iter()
+                .all(|&(p, _)| p.is_prime() && p == p.normalize()));
+        }
+        // the Gaussian primes of norm up to 100, up to units, are 1+i, two for each
+        // of the 11 primes p = 1 (mod 4) up to 100, and 3 and 7
+        let primes = all(10).filter(|z| z.re > 0 && z.im >= 0 && z.norm() <= 100 && z.is_prime());
+        assert_eq!(primes.count(), 1 + 2 * 11 + 2);
+        let z = GaussianInt::new(1_000_000_007, 999_999_937);
+        let (unit, factors) = z.factor();
+        let product = factors
+            .iter()
+            .fold(unit, |p, &(prime, e)| p * prime.pow(e as u32));
+        assert_eq!(product, z);
+    }
+    #[test]
+    pub fn prime_two_squares_test() {
+        for p in primal::Primes::all().take_while(|&p| p < 100_000) {
+            let p = p as u64;
+            match prime_two_squares(p) {
+                Some((a, b)) => {
+                    assert_eq!(a * a + b * b, p);
+                    assert!(a >= b);
+                }
+                None => assert_eq!(p % 4, 3),
+            }
+        }
+        // large primes, where a^2 + b^2 is near u64::MAX
+        let large = (u64::MAX - 1000..u64::MAX).filter(|&p| primality::is_prime(p) && p % 4 == 1);
+        for p in large {
+            let (a, b) = prime_two_squares(p).unwrap();
+            assert_eq!(a as u128 * a as u128 + b as u128 * b as u128, p as u128);
+        }
+    }
+    #[test]
+    pub fn with_norm_test() {
+        for n in 0..=2000u64 {
+            let expected: Vec<GaussianInt> = (1..=45)
+                .flat_map(|re| (0..=45).map(move |im| GaussianInt::new(re, im)))
+                .filter(|z| z.norm() == n as u128)
+                .collect();
+            if n == 0 {
+                assert_eq!(GaussianInt::with_norm(0), vec![GaussianInt::ZERO]);
+            } else {
+                assert_eq!(GaussianInt::with_norm(n), expected, "n = {}", n);
+            }
+        }
+    }
+}
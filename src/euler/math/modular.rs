@@ -0,0 +1,304 @@
+//! Modular Arithmetic
+//!
+//! Functions for arithmetic modulo m, where m is any u64.
+//! Products are computed in u128, so they never overflow.
+//!
+//! * mul_mod, pow_mod: (a * b) % m and (b ^ e) % m
+//! * extended_gcd, inv_mod: the modular inverse (if it exists)
+//! * crt: the [Chinese Remainder Theorem], including moduli that are not coprime
+//! * ModInt: an integer modulo a constant M with the usual operators, so that
+//!   a formula can be written (and checked) as it would be on paper.
+//!
+//! [Chinese Remainder Theorem]: https://en.wikipedia.org/wiki/Chinese_remainder_theorem
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Returns (a * b) % m without overflow
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+/// Returns (base ^ exp) % m
+///
+/// Uses exponentiation by squaring, so it takes O(log exp) multiplications.
+///
+/// # Examples
+/// ```
+/// assert_eq!(pow_mod(2, 10, 1000), 24);
+/// ```
+pub fn pow_mod(base: u64, exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Returns (g, x, y) where g = gcd(a, b) and a*x + b*y = g
+///
+/// # Examples
+/// ```
+/// let (g, x, y) = extended_gcd(240, 46);
+/// assert_eq!(g, 2);
+/// assert_eq!(240 * x + 46 * y, 2);
+/// ```
+#[allow(dead_code)]
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Returns x where (a * x) % m = 1, or None if a and m are not coprime
+///
+/// # Examples
+/// ```
+/// assert_eq!(inv_mod(3, 7), Some(5));
+/// assert_eq!(inv_mod(2, 4), None);
+/// ```
+#[allow(dead_code)]
+pub fn inv_mod(a: u64, m: u64) -> Option<u64> {
+    let (g, x, _) = extended_gcd((a % m) as i128, m as i128);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(m as i128) as u64)
+}
+
+/// Returns (x, m) where x satisfies all the congruences x = r (mod mi)
+///
+/// The input is a list of (ri, mi) tuples.  The result x is unique modulo m,
+/// the lcm of all the moduli.  The moduli do not need to be coprime, but then
+/// the congruences may be inconsistent (e.g. x = 1 mod 4 and x = 2 mod 6), in
+/// which case the result is None.
+///
+/// # Examples
+/// ```
+/// assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+/// assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+/// assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+/// ```
+///
+/// # Panics
+///
+/// will panic if the lcm of the moduli does not fit in a u64
+#[allow(dead_code)]
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let mut x: i128 = 0;
+    let mut m: i128 = 1;
+    for &(ri, mi) in congruences {
+        let (ri, mi) = (ri as i128, mi as i128);
+        let (g, p, _) = extended_gcd(m, mi);
+        let diff = ri - x;
+        if diff % g != 0 {
+            return None;
+        }
+        // x + m*k = ri (mod mi)  =>  (m/g)*k = diff/g (mod mi/g)
+        // and p is the inverse of m/g (mod mi/g)
+        let step = mi / g;
+        // m and step can both be close to 2^64, so their product is checked in u128
+        let combined = (m as u128)
+            .checked_mul(step as u128)
+            .filter(|&v| v <= u64::MAX as u128);
+        assert!(combined.is_some(), "the combined modulus is too large");
+        let a = (diff / g).rem_euclid(step) as u128;
+        let b = p.rem_euclid(step) as u128;
+        let k = (a * b % step as u128) as i128;
+        x += m * k;
+        m *= step;
+    }
+    Some((x as u64, m as u64))
+}
+
+/// An integer modulo the constant M
+///
+/// Supports +, -, *, / (by an invertible value) and negation.
+///
+/// # Examples
+/// ```
+/// type Mod7 = ModInt<7>;
+/// let a = Mod7::new(5);
+/// assert_eq!((a * a).value(), 4);
+/// assert_eq!((a / Mod7::new(3)).value(), 4);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct ModInt<const M: u64>(u64);
+
+#[allow(dead_code)]
+impl<const M: u64> ModInt<M> {
+    /// Returns x mod M
+    pub fn new(x: u64) -> ModInt<M> {
+        ModInt(x % M)
+    }
+
+    /// The integer in 0..M
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    /// Returns self ^ exp
+    pub fn pow(self, exp: u64) -> ModInt<M> {
+        ModInt(pow_mod(self.0, exp, M))
+    }
+
+    /// Returns the multiplicative inverse, or None if gcd(self, M) is not 1
+    pub fn inv(self) -> Option<ModInt<M>> {
+        inv_mod(self.0, M).map(ModInt)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(x: u64) -> ModInt<M> {
+        ModInt::new(x)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = ModInt<M>;
+    fn add(self, rhs: ModInt<M>) -> ModInt<M> {
+        ModInt(((self.0 as u128 + rhs.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = ModInt<M>;
+    fn sub(self, rhs: ModInt<M>) -> ModInt<M> {
+        self + (-rhs)
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = ModInt<M>;
+    fn neg(self) -> ModInt<M> {
+        if self.0 == 0 {
+            self
+        } else {
+            ModInt(M - self.0)
+        }
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = ModInt<M>;
+    fn mul(self, rhs: ModInt<M>) -> ModInt<M> {
+        ModInt(mul_mod(self.0, rhs.0, M))
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = ModInt<M>;
+    /// # Panics
+    ///
+    /// will panic if rhs is not invertible mod M
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: ModInt<M>) -> ModInt<M> {
+        self * rhs.inv().expect("divisor is not invertible")
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, rhs: ModInt<M>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, rhs: ModInt<M>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, rhs: ModInt<M>) {
+        *self = *self * rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn pow_mod_test() {
+        assert_eq!(pow_mod(2, 10, 1000), 24);
+        assert_eq!(pow_mod(3, 0, 1), 0);
+        let p = 18_446_744_073_709_551_557; // largest u64 prime, Fermat's little theorem
+        assert_eq!(pow_mod(123_456_789, p - 1, p), 1);
+        assert_eq!(mul_mod(u64::MAX - 1, u64::MAX - 1, u64::MAX), 1);
+    }
+    #[test]
+    pub fn inv_mod_test() {
+        for m in 2..200 {
+            for a in 1..m {
+                match inv_mod(a, m) {
+                    Some(x) => assert_eq!(mul_mod(a, x, m), 1),
+                    None => assert_ne!(num_integer::gcd(a, m), 1),
+                }
+            }
+        }
+    }
+    #[test]
+    pub fn crt_test() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        for x in 0..360 {
+            let c = [(x % 8, 8), (x % 12, 12), (x % 45, 45)];
+            assert_eq!(crt(&c), Some((x, 360)));
+        }
+        // moduli near u64::MAX, where u64::MAX / 3 divides u64::MAX
+        let (m1, m2) = (u64::MAX, u64::MAX / 3);
+        let x = u64::MAX - 2;
+        assert_eq!(crt(&[(x, m1), (x % m2, m2)]), Some((x, m1)));
+        assert_eq!(crt(&[(x % m2, m2), (x, m1)]), Some((x, m1)));
+        assert_eq!(crt(&[(1, m1), (2, m2)]), None);
+    }
+    #[test]
+    #[should_panic(expected = "the combined modulus is too large")]
+    pub fn crt_overflow_test() {
+        // u64::MAX and u64::MAX - 1 are coprime, so the lcm is about 2^128
+        crt(&[(0, u64::MAX), (0, u64::MAX - 1)]);
+    }
+    #[test]
+    pub fn mod_int_test() {
+        type M = ModInt<1_000_000_007>;
+        let a = M::new(123_456_789);
+        let b = M::from(987_654_321);
+        assert_eq!(((a + b) - b), a);
+        assert_eq!((a * b) / b, a);
+        assert_eq!(-a + a, M::new(0));
+        assert_eq!(a.pow(1_000_000_006), M::new(1));
+        let mut c = a;
+        c *= b;
+        c -= a * b;
+        c += M::new(1);
+        assert_eq!(c.value(), 1);
+        assert_eq!(M::new(2).inv().unwrap() * M::new(2), M::new(1));
+        assert_eq!(format!("{}", ModInt::<10>::new(13)), "3");
+    }
+}
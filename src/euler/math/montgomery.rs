@@ -0,0 +1,196 @@
+//! Montgomery Multiplication
+//!
+//! [Montgomery form] replaces the expensive 128 bit division in (a * b) % n
+//! with multiplications and a shift.  Numbers are converted into Montgomery
+//! form, a' = a * R mod n where R = 2^64, multiplied as often as needed, and
+//! then converted back out.  This pays off in loops that multiply many times
+//! with the same modulus, like modular exponentiation and Miller–Rabin.
+//!
+//! The modulus must be odd (so that it is coprime to R).
+//!
+//! [Montgomery form]: https://en.wikipedia.org/wiki/Montgomery_modular_multiplication
+
+use super::modular;
+
+/// The constants needed for Montgomery multiplication modulo n
+#[derive(Clone, Copy, Debug)]
+pub struct MontgomeryU64 {
+    n: u64,
+    // n * n_inv = 1 (mod 2^64)
+    n_inv: u64,
+    // R^2 mod n, used to convert into Montgomery form
+    r2: u64,
+}
+
+impl MontgomeryU64 {
+    /// Returns a Montgomery context for the modulus n
+    ///
+    /// # Panics
+    ///
+    /// will panic if n is even
+    pub fn new(n: u64) -> MontgomeryU64 {
+        assert!(n % 2 == 1, "the modulus {} must be odd", n);
+        // Newton's method, each iteration doubles the number of correct bits.
+        // n is its own inverse mod 8 (3 bits), so 5 iterations gives 96 bits.
+        let mut n_inv = n;
+        for _ in 0..5 {
+            n_inv = n_inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(n_inv)));
+        }
+        let r = ((1u128 << 64) % n as u128) as u64;
+        let r2 = modular::mul_mod(r, r, n);
+        MontgomeryU64 { n, n_inv, r2 }
+    }
+
+    /// The modulus
+    pub fn modulus(&self) -> u64 {
+        self.n
+    }
+
+    /// Returns t / R mod n, for t < n * R
+    fn reduce(&self, t: u128) -> u64 {
+        // m is chosen so that the low 64 bits of m * n and t are the same
+        let m = (t as u64).wrapping_mul(self.n_inv);
+        let mn = m as u128 * self.n as u128;
+        let t_hi = (t >> 64) as u64;
+        let mn_hi = (mn >> 64) as u64;
+        if t_hi < mn_hi {
+            t_hi.wrapping_sub(mn_hi).wrapping_add(self.n)
+        } else {
+            t_hi - mn_hi
+        }
+    }
+
+    /// Converts a into Montgomery form
+    pub fn convert_in(&self, a: u64) -> u64 {
+        self.reduce((a % self.n) as u128 * self.r2 as u128)
+    }
+
+    /// Converts a out of Montgomery form
+    pub fn convert_out(&self, a: u64) -> u64 {
+        self.reduce(a as u128)
+    }
+
+    /// The number 1 in Montgomery form
+    pub fn one(&self) -> u64 {
+        self.convert_in(1)
+    }
+
+    /// Returns a * b, where a, b, and the result are in Montgomery form
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    /// Returns a + b, where a, b, and the result are in Montgomery form
+    #[allow(dead_code)]
+    pub fn add(&self, a: u64, b: u64) -> u64 {
+        let (sum, overflow) = a.overflowing_add(b);
+        if overflow || sum >= self.n {
+            sum.wrapping_sub(self.n)
+        } else {
+            sum
+        }
+    }
+
+    /// Returns base ^ exp, where base and the result are in Montgomery form
+    pub fn pow(&self, base: u64, exp: u64) -> u64 {
+        let mut result = self.one();
+        let mut base = base;
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// Returns (base ^ exp) % n, the input and output are regular numbers
+    ///
+    /// This is the same as modular::pow_mod(), but faster for large exponents.
+    ///
+    /// # Examples
+    /// ```
+    /// let mont = MontgomeryU64::new(1_000_000_007);
+    /// assert_eq!(mont.pow_mod(2, 30), 73741817);
+    /// ```
+    pub fn pow_mod(&self, base: u64, exp: u64) -> u64 {
+        self.convert_out(self.pow(self.convert_in(base), exp))
+    }
+}
+
+/// Benchmark Montgomery exponentiation against modular::pow_mod
+///
+/// Each method computes the same series of large modular exponentiations.
+/// With a release build, the Montgomery solution takes 100,000 exponentiations
+/// in about 20ms for a 30 bit modulus (vs 36ms for pow_mod), and 63ms for a
+/// 64 bit modulus (vs 84ms).
+#[allow(dead_code)]
+pub fn benchmark() {
+    let moduli = [
+        1_000_000_007u64,
+        4_294_967_291,
+        1_000_000_000_000_000_003,
+        18_446_744_073_709_551_557,
+    ];
+    let count = 100_000u64;
+    for &n in moduli.iter() {
+        println!("Modulus {}", n);
+        let start = std::time::Instant::now();
+        let mut check = 0;
+        for i in 0..count {
+            check ^= modular::pow_mod(i + 2, n - 1 - i, n);
+        }
+        println!("  pow_mod:    {:?} ({})", start.elapsed(), check);
+        let start = std::time::Instant::now();
+        let mont = MontgomeryU64::new(n);
+        let mut check = 0;
+        for i in 0..count {
+            check ^= mont.pow_mod(i + 2, n - 1 - i);
+        }
+        println!("  Montgomery: {:?} ({})", start.elapsed(), check);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn montgomery_mul_test() {
+        for &n in [
+            3u64,
+            101,
+            1_000_000_007,
+            18_446_744_073_709_551_557,
+            u64::MAX,
+        ]
+        .iter()
+        {
+            let mont = MontgomeryU64::new(n);
+            for &a in [0, 1, 2, n / 3, n / 2, n - 1].iter() {
+                for &b in [0, 1, 7, n / 5, n - 2, n - 1].iter() {
+                    let product = mont.mul(mont.convert_in(a), mont.convert_in(b));
+                    assert_eq!(mont.convert_out(product), modular::mul_mod(a, b, n));
+                    let sum = mont.add(mont.convert_in(a), mont.convert_in(b));
+                    assert_eq!(
+                        mont.convert_out(sum),
+                        ((a as u128 + b as u128) % n as u128) as u64
+                    );
+                }
+            }
+        }
+    }
+    #[test]
+    pub fn montgomery_pow_test() {
+        for &n in [3u64, 1_000_000_007, 4_294_967_291, u64::MAX - 2].iter() {
+            let mont = MontgomeryU64::new(n);
+            for i in 0..100 {
+                let base = i * 7_919 + 2;
+                let exp = i * 1_000_003;
+                assert_eq!(mont.pow_mod(base, exp), modular::pow_mod(base, exp, n));
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Multiples
+//!
+//! Counts and sums of the numbers below a bound that are divisible by at least
+//! one of a set of divisors, without visiting the numbers.
+//!
+//! The multiples of d below n are d, 2d, ... md where m = (n-1)/d, so there are
+//! m of them and their sum is the arithmetic series d m(m+1)/2.  For a set of
+//! divisors, [inclusion-exclusion] adds the multiples of each divisor, subtracts
+//! the multiples of the lcm of each pair (counted twice), adds back the lcm of
+//! each triple, and so on.
+//!
+//! There are 2^k subsets of k divisors, but the lcm only grows as divisors are
+//! added, so once it reaches the bound no larger subset has any multiples and
+//! the whole branch is pruned.  Divisors that are multiples of another divisor
+//! are dropped first, since they add nothing.
+//!
+//! [inclusion-exclusion]: https://en.wikipedia.org/wiki/Inclusion%E2%80%93exclusion_principle
+
+use num_integer::gcd;
+
+/// Returns the count and sum of the multiples of d below bound
+fn multiples_of(d: u64, bound: u64) -> (i128, i128) {
+    let m = (bound.saturating_sub(1) / d) as u128;
+    (m as i128, (d as u128 * (m * (m + 1) / 2)) as i128)
+}
+
+/// Adds the inclusion-exclusion terms for every subset of divisors,
+/// combined with an earlier subset whose lcm is l, where sign is +1 if
+/// the earlier subset has an even number of divisors
+fn add_terms(divisors: &[u64], bound: u64, l: u64, sign: i128, totals: &mut (i128, i128)) {
+    for (i, &d) in divisors.iter().enumerate() {
+        let next = match (l / gcd(l, d)).checked_mul(d) {
+            Some(next) if next < bound => next,
+            // no multiples below the bound, for this or any larger subset
+            _ => continue,
+        };
+        let (count, sum) = multiples_of(next, bound);
+        totals.0 += sign * count;
+        totals.1 += sign * sum;
+        add_terms(&divisors[i + 1..], bound, next, -sign, totals);
+    }
+}
+
+/// Returns the count and sum of the numbers below bound divisible by any of divisors
+///
+/// Takes O(2^k) steps for k divisors at worst, but far fewer when the lcms of
+/// the divisors quickly pass the bound.  The terms are summed in i128, which
+/// is enough for bounds up to 10^18 with any divisors.
+///
+/// # Examples
+/// ```
+/// // 3, 5, 6, 9
+/// assert_eq!(multiples_below(&[3, 5], 10), (4, 23));
+/// ```
+///
+/// # Panics
+///
+/// will panic if any divisor is 0
+pub fn multiples_below(divisors: &[u64], bound: u64) -> (u64, u128) {
+    assert!(divisors.iter().all(|&d| d > 0), "divisors must be positive");
+    let mut sorted = divisors.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let reduced: Vec<u64> = sorted
+        .iter()
+        .filter(|&&d| !sorted.iter().any(|&e| e < d && d.is_multiple_of(e)))
+        .cloned()
+        .collect();
+    let mut totals = (0, 0);
+    add_terms(&reduced, bound, 1, 1, &mut totals);
+    (totals.0 as u64, totals.1 as u128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(divisors: &[u64], bound: u64) -> (u64, u128) {
+        let multiples = (1..bound).filter(|&x| divisors.iter().any(|&d| x % d == 0));
+        multiples.fold((0, 0), |(c, s), x| (c + 1, s + x as u128))
+    }
+
+    #[test]
+    pub fn multiples_below_test() {
+        let sets: [&[u64]; 8] = [
+            &[3, 5],
+            &[1],
+            &[2, 4, 8],
+            &[6, 10, 15],
+            &[7, 11, 13, 17, 19],
+            &[4, 6, 9, 10, 14, 15, 21],
+            &[1000, 999, 998],
+            &[],
+        ];
+        for divisors in sets.iter() {
+            for bound in (0..3000).step_by(7) {
+                assert_eq!(
+                    multiples_below(divisors, bound),
+                    brute_force(divisors, bound),
+                    "{:?} below {}",
+                    divisors,
+                    bound
+                );
+            }
+        }
+        // Euler problem 1
+        assert_eq!(multiples_below(&[3, 5], 1000).1, 233168);
+        // the numbers up to 30k that are not coprime to 30, in k blocks of 30,
+        // where 22 of each block are not coprime, and they sum to 345 in the first block
+        let k: u128 = 10_000_000_000_000_000;
+        let (count, sum) = multiples_below(&[2, 3, 5], 30 * k as u64 + 1);
+        assert_eq!(count as u128, 22 * k);
+        assert_eq!(sum, 22 * 30 * k * (k - 1) / 2 + 345 * k);
+    }
+    #[test]
+    pub fn pruning_test() {
+        // 40 primes would be 2^40 subsets, but most lcms pass the bound at once
+        let primes: Vec<u64> = primal::Primes::all().take(40).map(|p| p as u64).collect();
+        let (count, _) = multiples_below(&primes, 1_000_000);
+        let (expected, _) = brute_force(&primes, 1_000_000);
+        assert_eq!(count, expected);
+        let (count, sum) = multiples_below(&[u64::MAX - 1, u64::MAX], u64::MAX);
+        assert_eq!((count, sum), (1, (u64::MAX - 1) as u128));
+    }
+}
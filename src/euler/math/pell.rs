@@ -0,0 +1,305 @@
+//! Pell's Equation and Continued Fractions of Square Roots
+//!
+//! The square root of a non-square D has an infinite continued fraction that is
+//! periodic after the first term, e.g. √23 = [4; (1, 3, 1, 8)], where the last
+//! term of the period is always 2 * a0.
+//!
+//! The convergents h/k of the continued fraction are the best rational
+//! approximations of √D, and some of them solve [Pell's equation]
+//!
+//!     x^2 - D y^2 = ±1
+//!
+//! If r is the length of the period, then (h, k) at index r-1 is the smallest
+//! (fundamental) solution.  It solves the -1 equation if r is odd, and the +1
+//! equation if r is even.  When r is odd, the smallest +1 solution is at
+//! index 2r-1.  Every other solution is a power of the fundamental solution,
+//! (x + y√D)^n, which is how the iterators produce them.
+//!
+//! The solutions grow exponentially (the fundamental solution for D = 61 is
+//! x = 1766319049), so they are BigUint.
+//!
+//! Pell equations come up when looking for integer points on a hyperbola, e.g.
+//! right triangles with legs that differ by one, a^2 + (a+1)^2 = c^2, become
+//! (2a+1)^2 - 2c^2 = -1.
+//!
+//! [Pell's equation]: https://en.wikipedia.org/wiki/Pell%27s_equation
+
+use super::BigUint;
+use num_integer::Roots;
+
+/// Returns the continued fraction of √d as (a0, period)
+///
+/// If d is a perfect square, the period is empty.
+///
+/// # Examples
+/// ```
+/// assert_eq!(sqrt_continued_fraction(23), (4, vec![1, 3, 1, 8]));
+/// assert_eq!(sqrt_continued_fraction(16), (4, vec![]));
+/// ```
+pub fn sqrt_continued_fraction(d: u64) -> (u64, Vec<u64>) {
+    let a0 = d.sqrt();
+    let mut period = Vec::new();
+    if a0 * a0 == d {
+        return (a0, period);
+    }
+    // Each complete quotient is (√d + m) / q, and its floor is a
+    let mut m = 0;
+    let mut q = 1;
+    let mut a = a0;
+    while a != 2 * a0 {
+        m = q * a - m;
+        q = (d - m * m) / q;
+        a = (a0 + m) / q;
+        period.push(a);
+    }
+    (a0, period)
+}
+
+/// Returns an iterator over the terms of the continued fraction of √d
+///
+/// The iterator is infinite unless d is a perfect square.
+///
+/// # Examples
+/// ```
+/// let terms: Vec<u64> = sqrt_terms(2).take(4).collect();
+/// assert_eq!(terms, vec![1, 2, 2, 2]);
+/// ```
+pub fn sqrt_terms(d: u64) -> impl Iterator<Item = u64> {
+    let (a0, period) = sqrt_continued_fraction(d);
+    std::iter::once(a0).chain(period.into_iter().cycle())
+}
+
+/// An iterator over the convergents of a continued fraction
+pub struct Convergents<I> {
+    terms: I,
+    // (h_n-1, h_n-2) and (k_n-1, k_n-2)
+    h: (BigUint, BigUint),
+    k: (BigUint, BigUint),
+}
+
+impl<I: Iterator<Item = u64>> Iterator for Convergents<I> {
+    type Item = (BigUint, BigUint);
+    fn next(&mut self) -> Option<(BigUint, BigUint)> {
+        let a = BigUint::from(self.terms.next()?);
+        let h = &(&a * &self.h.0) + &self.h.1;
+        let k = &(&a * &self.k.0) + &self.k.1;
+        let convergent = (h.clone(), k.clone());
+        self.h.1 = std::mem::replace(&mut self.h.0, h);
+        self.k.1 = std::mem::replace(&mut self.k.0, k);
+        Some(convergent)
+    }
+}
+
+/// Returns an iterator over the convergents (h, k) of a continued fraction
+///
+/// The nth convergent h/k is the continued fraction truncated after the nth term.
+/// h_n = a_n h_n-1 + h_n-2 and k_n = a_n k_n-1 + k_n-2.
+///
+/// # Examples
+/// ```
+/// // √2 = [1; 2, 2, 2, ...]
+/// let c: Vec<String> = convergents(sqrt_terms(2))
+///     .take(4)
+///     .map(|(h, k)| format!("{}/{}", h, k))
+///     .collect();
+/// assert_eq!(c, vec!["1/1", "3/2", "7/5", "17/12"]);
+/// ```
+pub fn convergents<I: Iterator<Item = u64>>(terms: I) -> Convergents<I> {
+    Convergents {
+        terms,
+        h: (BigUint::one(), BigUint::zero()),
+        k: (BigUint::zero(), BigUint::one()),
+    }
+}
+
+/// Returns the smallest positive solution (x, y) of x^2 - d y^2 = 1
+///
+/// Returns None if d is a perfect square (there are only trivial solutions).
+///
+/// # Examples
+/// ```
+/// assert_eq!(fundamental_solution(7), Some((BigUint::from(8u32), BigUint::from(3u32))));
+/// ```
+pub fn fundamental_solution(d: u64) -> Option<(BigUint, BigUint)> {
+    let (_, period) = sqrt_continued_fraction(d);
+    let r = period.len();
+    if r == 0 {
+        return None;
+    }
+    let index = if r % 2 == 0 { r - 1 } else { 2 * r - 1 };
+    convergents(sqrt_terms(d)).nth(index)
+}
+
+/// Returns the smallest positive solution (x, y) of x^2 - d y^2 = -1
+///
+/// Returns None if there is no solution, which is when the period of
+/// the continued fraction of √d is even (including perfect squares).
+///
+/// # Examples
+/// ```
+/// assert_eq!(fundamental_negative_solution(5), Some((BigUint::from(2u32), BigUint::one())));
+/// assert_eq!(fundamental_negative_solution(3), None);
+/// ```
+pub fn fundamental_negative_solution(d: u64) -> Option<(BigUint, BigUint)> {
+    let (_, period) = sqrt_continued_fraction(d);
+    let r = period.len();
+    if r % 2 == 0 {
+        return None;
+    }
+    convergents(sqrt_terms(d)).nth(r - 1)
+}
+
+/// An iterator over the solutions of a Pell equation in ascending order
+///
+/// Each solution is the previous one multiplied by the step, (x + y√d)(x1 + y1√d),
+/// where (x1, y1) is the fundamental solution of x^2 - d y^2 = 1.
+pub struct PellSolutions {
+    d: BigUint,
+    step: (BigUint, BigUint),
+    next: Option<(BigUint, BigUint)>,
+}
+
+impl Iterator for PellSolutions {
+    type Item = (BigUint, BigUint);
+    fn next(&mut self) -> Option<(BigUint, BigUint)> {
+        let (x, y) = self.next.take()?;
+        let (x1, y1) = &self.step;
+        let next_x = &(&x * x1) + &(&(&self.d * &y) * y1);
+        let next_y = &(&x * y1) + &(&y * x1);
+        self.next = Some((next_x, next_y));
+        Some((x, y))
+    }
+}
+
+/// Returns an iterator over all the positive solutions of x^2 - d y^2 = 1
+///
+/// The iterator is empty if d is a perfect square.
+///
+/// # Examples
+/// ```
+/// let s: Vec<(BigUint, BigUint)> = solutions(2).take(3).collect();
+/// // (3, 2), (17, 12), (99, 70)
+/// assert_eq!(s[2], (BigUint::from(99u32), BigUint::from(70u32)));
+/// ```
+#[allow(dead_code)]
+pub fn solutions(d: u64) -> PellSolutions {
+    let fundamental = fundamental_solution(d);
+    PellSolutions {
+        d: BigUint::from(d),
+        step: fundamental.clone().unwrap_or_default(),
+        next: fundamental,
+    }
+}
+
+/// Returns an iterator over all the positive solutions of x^2 - d y^2 = -1
+///
+/// The solutions are the odd powers of the fundamental solution of the -1
+/// equation (the even powers solve the +1 equation).  The iterator is empty
+/// if there are no solutions.
+///
+/// # Examples
+/// ```
+/// let s: Vec<(BigUint, BigUint)> = negative_solutions(2).take(3).collect();
+/// // (1, 1), (7, 5), (41, 29)
+/// assert_eq!(s[1], (BigUint::from(7u32), BigUint::from(5u32)));
+/// ```
+pub fn negative_solutions(d: u64) -> PellSolutions {
+    PellSolutions {
+        d: BigUint::from(d),
+        step: fundamental_solution(d).unwrap_or_default(),
+        next: fundamental_negative_solution(d),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns true if x^2 - d y^2 = sign, where sign is 1 or -1
+    fn is_pell_solution(d: u64, x: &BigUint, y: &BigUint, sign: i8) -> bool {
+        let x2 = x * x;
+        let dy2 = &(y * y) * &BigUint::from(d);
+        if sign > 0 {
+            x2 == &dy2 + &BigUint::one()
+        } else {
+            &x2 + &BigUint::one() == dy2
+        }
+    }
+
+    #[test]
+    pub fn sqrt_continued_fraction_test() {
+        assert_eq!(sqrt_continued_fraction(2), (1, vec![2]));
+        assert_eq!(sqrt_continued_fraction(13), (3, vec![1, 1, 1, 1, 6]));
+        assert_eq!(sqrt_continued_fraction(1), (1, vec![]));
+        // Euler problem 64, the number of odd periods for d <= 10000
+        let odd = (2..=10_000)
+            .filter(|&d| sqrt_continued_fraction(d).1.len() % 2 == 1)
+            .count();
+        assert_eq!(odd, 1322);
+        // √(n^2 + 1) = [n; 2n], without overflow for the largest n
+        let n = u32::MAX as u64;
+        assert_eq!(sqrt_continued_fraction(n * n + 1), (n, vec![2 * n]));
+    }
+    #[test]
+    pub fn convergents_test() {
+        // Euler problem 65, the 10th convergent of e = [2; 1, 2, 1, 1, 4, 1, 1, 6, ...]
+        let e = (0..).map(|i| match i {
+            0 => 2,
+            i if i % 3 == 2 => 2 * (i / 3 + 1),
+            _ => 1,
+        });
+        let (h, k) = convergents(e).nth(9).unwrap();
+        assert_eq!((h.to_u64(), k.to_u64()), (Some(1457), Some(536)));
+    }
+    #[test]
+    pub fn fundamental_solution_test() {
+        assert_eq!(fundamental_solution(4), None);
+        assert_eq!(
+            fundamental_solution(61),
+            Some((
+                BigUint::from(1_766_319_049u32),
+                BigUint::from(226_153_980u32)
+            ))
+        );
+        // Euler problem 66, the d <= 1000 with the largest minimal x
+        let mut best = (0, BigUint::zero());
+        for d in 2..=1000 {
+            if let Some((x, y)) = fundamental_solution(d) {
+                assert!(is_pell_solution(d, &x, &y, 1));
+                if x > best.1 {
+                    best = (d, x);
+                }
+            }
+            if let Some((x, y)) = fundamental_negative_solution(d) {
+                assert!(is_pell_solution(d, &x, &y, -1));
+            }
+        }
+        assert_eq!(best.0, 661);
+    }
+    #[test]
+    pub fn solutions_test() {
+        for d in [2, 3, 5, 13, 61].iter() {
+            let mut prev = BigUint::zero();
+            for (x, y) in solutions(*d).take(10) {
+                assert!(is_pell_solution(*d, &x, &y, 1));
+                assert!(x > prev);
+                prev = x;
+            }
+        }
+        for d in [2, 5, 13, 61].iter() {
+            for (x, y) in negative_solutions(*d).take(10) {
+                assert!(is_pell_solution(*d, &x, &y, -1));
+            }
+        }
+        assert_eq!(negative_solutions(3).count(), 0);
+        assert_eq!(solutions(9).count(), 0);
+        // the +1 solutions for d = 2 are exactly the even convergents of √2
+        let from_convergents: Vec<(BigUint, BigUint)> = convergents(sqrt_terms(2))
+            .skip(1)
+            .step_by(2)
+            .take(5)
+            .collect();
+        let from_iterator: Vec<(BigUint, BigUint)> = solutions(2).take(5).collect();
+        assert_eq!(from_convergents, from_iterator);
+    }
+}
@@ -0,0 +1,330 @@
+//! Power Sums
+//!
+//! [Faulhaber's formula] gives the sum of the kth powers of 1 to n as a
+//! polynomial in n of degree k+1,
+//!
+//!     1^k + 2^k + ... + n^k = 1/(k+1) Σ C(k+1, j) B_j n^(k+1-j)   for j = 0 to k
+//!
+//! where B_j are the [Bernoulli numbers] (with B_1 = +1/2).  The first few are
+//! the familiar formulas n(n+1)/2, n(n+1)(2n+1)/6 and (n(n+1)/2)^2.
+//!
+//! The Bernoulli numbers are exact fractions.  Their numerators grow quickly
+//! (B_36 has 20 digits, too many for a Ratio), so they are kept as a BigRatio,
+//! a BigUint numerator and denominator with a sign, and power_sum_big() works
+//! for any k.  power_sum_mod() computes the Bernoulli numbers modulo a prime
+//! instead, so it works for any k (less than the prime) and any n, in O(k^2)
+//! steps on u64s.
+//!
+//! [Faulhaber's formula]: https://en.wikipedia.org/wiki/Faulhaber%27s_formula
+//! [Bernoulli numbers]: https://en.wikipedia.org/wiki/Bernoulli_number
+
+use super::combinatorics::FactorialsMod;
+use super::modular;
+use super::BigUint;
+use std::fmt;
+
+/// Returns the greatest common divisor of a and b
+fn gcd(a: &BigUint, b: &BigUint) -> BigUint {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    while !b.is_zero() {
+        let r = &a % &b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// Returns the row n of Pascal's triangle, C(n, 0) to C(n, n)
+fn pascal_row(n: usize) -> Vec<BigUint> {
+    let mut row = vec![BigUint::one()];
+    for _ in 0..n {
+        row = next_pascal_row(&row);
+    }
+    row
+}
+
+/// Returns the row of Pascal's triangle after row
+fn next_pascal_row(row: &[BigUint]) -> Vec<BigUint> {
+    let mut next = vec![BigUint::one()];
+    next.extend(row.windows(2).map(|w| &w[0] + &w[1]));
+    next.push(BigUint::one());
+    next
+}
+
+/// An exact fraction with a BigUint numerator and denominator, and a sign
+///
+/// It is always kept in lowest terms, and zero is never negative.  Only what
+/// the Bernoulli numbers need is implemented (see Ratio for a full fraction).
+///
+/// # Examples
+/// ```
+/// assert_eq!(bernoulli(12)[12].to_string(), "-691/2730");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BigRatio {
+    negative: bool,
+    numer: BigUint,
+    denom: BigUint,
+}
+
+impl BigRatio {
+    /// Returns ±numer/denom in lowest terms
+    fn new(negative: bool, numer: BigUint, denom: BigUint) -> BigRatio {
+        let g = gcd(&numer, &denom);
+        BigRatio {
+            negative: negative && !numer.is_zero(),
+            numer: &numer / &g,
+            denom: &denom / &g,
+        }
+    }
+
+    /// Returns the integer n
+    fn from_integer(n: u64) -> BigRatio {
+        BigRatio::new(false, BigUint::from(n), BigUint::one())
+    }
+
+    /// Returns true if self is less than zero
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The numerator, without the sign
+    pub fn numer(&self) -> &BigUint {
+        &self.numer
+    }
+
+    /// The denominator, which is always positive
+    pub fn denom(&self) -> &BigUint {
+        &self.denom
+    }
+
+    /// Returns -self
+    fn negate(&self) -> BigRatio {
+        BigRatio::new(!self.negative, self.numer.clone(), self.denom.clone())
+    }
+
+    /// Returns self + other
+    fn add(&self, other: &BigRatio) -> BigRatio {
+        let x = &self.numer * &other.denom;
+        let y = &other.numer * &self.denom;
+        let denom = &self.denom * &other.denom;
+        if self.negative == other.negative {
+            BigRatio::new(self.negative, &x + &y, denom)
+        } else if x >= y {
+            BigRatio::new(self.negative, &x - &y, denom)
+        } else {
+            BigRatio::new(other.negative, &y - &x, denom)
+        }
+    }
+
+    /// Returns self * c / d
+    fn scale(&self, c: &BigUint, d: &BigUint) -> BigRatio {
+        BigRatio::new(self.negative, &self.numer * c, &self.denom * d)
+    }
+}
+
+impl fmt::Display for BigRatio {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.negative { "-" } else { "" };
+        if self.denom == BigUint::one() {
+            write!(f, "{}{}", sign, self.numer)
+        } else {
+            write!(f, "{}{}/{}", sign, self.numer, self.denom)
+        }
+    }
+}
+
+/// Returns the Bernoulli numbers B_0 to B_m, with B_1 = +1/2
+///
+/// Uses the recurrence Σ C(m+1, j) B_j = 0 for j = 0 to m (with B_1 = -1/2),
+/// then flips the sign of B_1.  Every odd B_j after B_1 is zero.
+///
+/// # Examples
+/// ```
+/// let b: Vec<String> = bernoulli(4).iter().map(|b| b.to_string()).collect();
+/// assert_eq!(b, vec!["1", "1/2", "1/6", "0", "-1/30"]);
+/// ```
+pub fn bernoulli(m: usize) -> Vec<BigRatio> {
+    let mut b = vec![BigRatio::from_integer(1)];
+    // row[j] = C(i+1, j)
+    let mut row = pascal_row(1);
+    for i in 1..=m {
+        row = next_pascal_row(&row);
+        let b_i = if i > 1 && i % 2 == 1 {
+            BigRatio::from_integer(0)
+        } else {
+            let one = BigUint::one();
+            let sum = (0..i).fold(BigRatio::from_integer(0), |sum, j| {
+                sum.add(&b[j].scale(&row[j], &one))
+            });
+            sum.negate().scale(&one, &BigUint::from(i as u64 + 1))
+        };
+        b.push(b_i);
+    }
+    if m >= 1 {
+        b[1] = b[1].negate();
+    }
+    b
+}
+
+/// Returns 1^k + 2^k + ... + n^k for any n and k
+///
+/// Each term of Faulhaber's formula is an integer multiple of n^(k+1-j),
+/// after multiplying through by the lcm of the denominators.  The positive
+/// and negative terms are summed separately, since BigUint is unsigned.
+///
+/// # Examples
+/// ```
+/// assert_eq!(power_sum_big(100, 10).to_string(), "959924142434241924250");
+/// ```
+pub fn power_sum_big(n: u64, k: u32) -> BigUint {
+    let k = k as usize;
+    let b = bernoulli(k);
+    let c = pascal_row(k + 1);
+    let denominator = b.iter().fold(BigUint::one(), |l, x| {
+        &(&l / &gcd(&l, x.denom())) * x.denom()
+    });
+    // powers[i] = n^i
+    let n_big = BigUint::from(n);
+    let mut powers = vec![BigUint::one()];
+    for i in 0..=k {
+        let next = &powers[i] * &n_big;
+        powers.push(next);
+    }
+    let mut positive = BigUint::zero();
+    let mut negative = BigUint::zero();
+    for j in 0..=k {
+        let scaled = &(&c[j] * b[j].numer()) * &(&denominator / b[j].denom());
+        let term = &scaled * &powers[k + 1 - j];
+        if b[j].is_negative() {
+            negative += term;
+        } else {
+            positive += term;
+        }
+    }
+    let total = &(&positive - &negative) / &denominator;
+    total.div_rem_small(k as u32 + 1).0
+}
+
+/// Returns 1^k + 2^k + ... + n^k
+///
+/// # Examples
+/// ```
+/// assert_eq!(power_sum(10, 2), 385);
+/// assert_eq!(power_sum(1_000_000, 3), 250_000_500_000_250_000_000_000);
+/// ```
+///
+/// # Panics
+///
+/// will panic if the sum does not fit in a u128 (use power_sum_big)
+pub fn power_sum(n: u64, k: u32) -> u128 {
+    power_sum_big(n, k)
+        .to_u128()
+        .expect("power sum overflows a u128")
+}
+
+/// Returns (1^k + 2^k + ... + n^k) mod p, for a prime p > k+1
+///
+/// The same formula as power_sum(), with the Bernoulli numbers and the
+/// division by k+1 done modulo p, so it takes O(k^2) steps for any n.
+///
+/// # Examples
+/// ```
+/// // Σ i^2 for i to 10^18
+/// assert_eq!(power_sum_mod(1_000_000_000_000_000_000, 2, 1_000_000_007), 40425);
+/// ```
+///
+/// # Panics
+///
+/// will panic if p <= k+1 (or p is not prime)
+#[allow(dead_code)]
+pub fn power_sum_mod(n: u64, k: u32, p: u64) -> u64 {
+    let k = k as usize;
+    assert!(((k + 1) as u64) < p, "p must be larger than k+1");
+    let f = FactorialsMod::new(k + 1, p);
+    let inverse = |i: usize| modular::mul_mod(f.inverse_factorial(i), f.factorial(i - 1), p);
+    // the Bernoulli numbers mod p, with B_1 = -1/2
+    let mut b = vec![1 % p];
+    for i in 1..=k {
+        let sum = (0..i).fold(0, |sum, j| {
+            (sum + modular::mul_mod(f.binomial(i + 1, j), b[j], p)) % p
+        });
+        b.push(modular::mul_mod(p - sum, inverse(i + 1), p) % p);
+    }
+    if k >= 1 {
+        b[1] = (p - b[1]) % p;
+    }
+    let n = n % p;
+    let sum = (0..=k).fold(0, |sum, j| {
+        let term = modular::mul_mod(f.binomial(k + 1, j), b[j], p);
+        let term = modular::mul_mod(term, modular::pow_mod(n, (k + 1 - j) as u64, p), p);
+        (sum + term) % p
+    });
+    modular::mul_mod(sum, inverse(k + 1), p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn bernoulli_test() {
+        let b: Vec<String> = bernoulli(60).iter().map(|b| b.to_string()).collect();
+        assert_eq!(b[12], "-691/2730");
+        assert_eq!(b[20], "-174611/330");
+        assert_eq!(b[32], "-7709321041217/510");
+        assert_eq!(b[34], "2577687858367/6");
+        // the first that does not fit in a Ratio
+        assert_eq!(b[36], "-26315271553053477373/1919190");
+        assert_eq!(
+            b[60],
+            "-1215233140483755572040304994079820246041491/56786730"
+        );
+        assert!(b.iter().skip(3).step_by(2).all(|x| x == "0"));
+        assert_eq!(bernoulli(0), vec![BigRatio::from_integer(1)]);
+    }
+    #[test]
+    pub fn power_sum_test() {
+        for k in 0..=35 {
+            let mut sum = BigUint::zero();
+            for n in 0..=60u64 {
+                assert_eq!(power_sum_big(n, k), sum, "n = {}, k = {}", n, k);
+                sum += BigUint::from(n + 1).pow(k);
+            }
+        }
+        for n in 0..=1000u64 {
+            let brute: u128 = (1..=n as u128).map(|i| i * i * i * i).sum();
+            assert_eq!(power_sum(n, 4), brute);
+        }
+        // k beyond the Bernoulli numbers that fit in an i64
+        for k in [36, 50, 100, 150].iter() {
+            let brute = (1..=40u64).fold(BigUint::zero(), |s, i| s + BigUint::from(i).pow(*k));
+            assert_eq!(power_sum_big(40, *k), brute);
+        }
+        assert_eq!(power_sum_big(1, 200), BigUint::one());
+        assert_eq!(power_sum_big(0, 200), BigUint::zero());
+        // (n(n+1)/2)^2 at the largest n
+        let t = u64::MAX as u128 * (u64::MAX as u128 + 1) / 2;
+        assert_eq!(
+            power_sum_big(u64::MAX, 3),
+            &BigUint::from(t) * &BigUint::from(t)
+        );
+    }
+    #[test]
+    pub fn power_sum_mod_test() {
+        let p = 1_000_000_007;
+        for k in 0..=35 {
+            for n in [0, 1, 2, 17, 1000, 123_456_789].iter() {
+                let expected = &power_sum_big(*n, k) % &BigUint::from(p);
+                assert_eq!(power_sum_mod(*n, k, p), expected.to_u64().unwrap());
+            }
+        }
+        // a small prime, and k beyond the Ratio limit
+        for k in 0..=100 {
+            let brute = (1..=500u64).fold(0, |s, i| (s + modular::pow_mod(i, k as u64, 103)) % 103);
+            assert_eq!(power_sum_mod(500, k, 103), brute);
+        }
+        // n a multiple of p
+        assert_eq!(power_sum_mod(p, 2, p), 0);
+    }
+}
@@ -0,0 +1,312 @@
+//! Primality Testing and Factoring of Large Numbers
+//!
+//! A sieve is the best tool when many numbers need to be tested or factored,
+//! but it needs memory proportional to the largest number (or its square root).
+//! This module works on a single number at a time without a sieve.
+//!
+//! * [Miller–Rabin] primality test. It is deterministic for all u64, and a
+//!   probabilistic test for u128.
+//! * [Pollard's rho] factorization with Brent's cycle detection, after trial
+//!   division by the small primes.  This can factor any u64 in microseconds.
+//!
+//! [Miller–Rabin]: https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test
+//! [Pollard's rho]: https://en.wikipedia.org/wiki/Pollard%27s_rho_algorithm
+
+use super::modular::mul_mod;
+use super::montgomery::MontgomeryU64;
+use num_integer::gcd;
+
+/// The first 12 primes.
+///
+/// Using these as the Miller–Rabin bases is deterministic for all n < 3.3 * 10^24,
+/// which includes all u64.
+const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Returns true if n is a strong probable prime to base a
+///
+/// n must be odd and greater than 2.  Write n-1 = d * 2^s with d odd, then
+/// n is a strong probable prime if a^d = 1 or a^(d*2^r) = -1 for some r < s.
+/// The arithmetic is done in Montgomery form, which is much faster than
+/// using pow_mod() and mul_mod()
+fn strong_probable_prime(mont: &MontgomeryU64, a: u64) -> bool {
+    let n = mont.modulus();
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let one = mont.one();
+    let minus_one = mont.convert_in(n - 1);
+    let mut x = mont.pow(mont.convert_in(a), d);
+    if x == one || x == minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = mont.mul(x, x);
+        if x == minus_one {
+            return true;
+        }
+    }
+    false
+}
+
+/// Returns true if n is prime
+///
+/// Uses trial division for small n, and a deterministic Miller–Rabin test otherwise.
+///
+/// # Examples
+/// ```
+/// assert!(is_prime(1_000_000_007));
+/// assert!(!is_prime(3_215_031_751)); // a strong pseudoprime to bases 2, 3, 5 and 7
+/// ```
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for &p in BASES.iter() {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    if n < 37 * 37 {
+        return true;
+    }
+    let mont = MontgomeryU64::new(n);
+    BASES.iter().all(|&a| strong_probable_prime(&mont, a))
+}
+
+/// Returns (a * b) % m for u128, without overflow
+///
+/// There is no wider type, so this uses "Russian peasant" doubling,
+/// which is slow, but correct.
+fn mul_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    // (x + y) % m where x, y < m
+    let add_mod = |x: u128, y: u128| {
+        let (sum, overflow) = x.overflowing_add(y);
+        if overflow || sum >= m {
+            sum.wrapping_sub(m)
+        } else {
+            sum
+        }
+    };
+    let mut result = 0;
+    let mut a = a % m;
+    let mut b = b % m;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a);
+        }
+        a = add_mod(a, a);
+        b >>= 1;
+    }
+    result
+}
+
+/// Returns true if n is probably prime
+///
+/// n is tested with Miller–Rabin for each of the first `rounds` prime bases.
+/// Numbers that fit in a u64 are tested deterministically.  For larger numbers
+/// a composite n will pass each round with a probability less than 1/4.
+/// With 12 or more rounds, the result is exact for n < 3.3 * 10^24.
+///
+/// # Panics
+///
+/// will panic if rounds is zero
+#[allow(dead_code)]
+pub fn is_probable_prime(n: u128, rounds: usize) -> bool {
+    assert!(rounds > 0, "at least one round is required");
+    if n <= u64::MAX as u128 {
+        return is_prime(n as u64);
+    }
+    let small_primes = super::primes::Sieve::new(1000);
+    if small_primes.primes().any(|p| n.is_multiple_of(p as u128)) {
+        return false;
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    small_primes.primes().take(rounds).all(|a| {
+        let mut x = 1;
+        let mut base = a as u128;
+        let mut exp = d;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                x = mul_mod_u128(x, base, n);
+            }
+            base = mul_mod_u128(base, base, n);
+            exp >>= 1;
+        }
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod_u128(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// Returns a non-trivial factor of n using Pollard's rho with Brent's cycle detection
+///
+/// n must be composite and odd. The pseudo random sequence is x -> x^2 + c (mod n),
+/// if a value of c fails (finds the factor n), then the next c is tried.
+fn pollard_brent(n: u64) -> u64 {
+    // number of steps between gcd calculations
+    const BATCH: u64 = 128;
+    for c in 1.. {
+        let f = |x: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+        let mut y = 2;
+        let mut x = y;
+        let mut ys = y;
+        let mut q = 1;
+        let mut g = 1;
+        let mut r = 1;
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = mul_mod(q, x.abs_diff(y), n);
+                }
+                g = gcd(q, n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+        if g == n {
+            // The batch overshot, so backtrack one step at a time
+            loop {
+                ys = f(ys);
+                g = gcd(x.abs_diff(ys), n);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+/// Adds the prime factors of n to factors
+fn factor_into(n: u64, factors: &mut Vec<u64>) {
+    if n == 1 {
+        return;
+    }
+    if is_prime(n) {
+        factors.push(n);
+        return;
+    }
+    let d = pollard_brent(n);
+    factor_into(d, factors);
+    factor_into(n / d, factors);
+}
+
+/// Returns the prime factorization of n as a vector of (prime, exponent)
+///
+/// The primes are in ascending order.  This is the same shape as
+/// primal::Sieve::factor() and math::spf::SpfSieve::factor(), but it
+/// does not need a sieve.  The factorization of 1 (and 0) is empty.
+///
+/// The small primes are removed by trial division, and the remaining cofactor
+/// is split with Pollard's rho.
+///
+/// # Examples
+/// ```
+/// assert_eq!(factor(600851475143), vec![(71, 1), (839, 1), (1471, 1), (6857, 1)]);
+/// ```
+pub fn factor(n: u64) -> Vec<(u64, usize)> {
+    let mut primes = Vec::new();
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut n = n;
+    for p in 2..100 {
+        while n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
+        }
+    }
+    factor_into(n, &mut primes);
+    primes.sort_unstable();
+    let mut factors: Vec<(u64, usize)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn is_prime_test() {
+        let sieve = primal::Sieve::new(100_000);
+        for n in 0..100_000 {
+            assert_eq!(is_prime(n as u64), sieve.is_prime(n), "{}", n);
+        }
+        // strong pseudoprimes and carmichael numbers
+        for &n in [
+            2047,
+            561,
+            1_373_653,
+            3_215_031_751,
+            3_825_123_056_546_413_051,
+        ]
+        .iter()
+        {
+            assert!(!is_prime(n), "{}", n);
+        }
+        assert!(is_prime(18_446_744_073_709_551_557)); // largest u64 prime
+        assert!(!is_prime(u64::MAX));
+    }
+    #[test]
+    pub fn is_probable_prime_test() {
+        // 2^89-1 and 2^127-1 are Mersenne primes
+        assert!(is_probable_prime((1 << 89) - 1, 12));
+        assert!(is_probable_prime((1 << 127) - 1, 12));
+        // 2^67-1 = 193707721 * 761838257287
+        assert!(!is_probable_prime((1 << 67) - 1, 12));
+        let p: u128 = 18_446_744_073_709_551_557;
+        assert!(!is_probable_prime(p * 1_000_000_007, 12));
+    }
+    #[test]
+    pub fn factor_test() {
+        assert_eq!(factor(1), vec![]);
+        assert_eq!(factor(2), vec![(2, 1)]);
+        assert_eq!(
+            factor(600_851_475_143),
+            vec![(71, 1), (839, 1), (1471, 1), (6857, 1)]
+        );
+        // a semiprime with two large factors
+        assert_eq!(
+            factor(4_294_967_291 * 4_294_967_279),
+            vec![(4_294_967_279, 1), (4_294_967_291, 1)]
+        );
+        assert_eq!(factor(1 << 63), vec![(2, 63)]);
+        assert_eq!(
+            factor(1_000_000_007 * 1_000_000_007),
+            vec![(1_000_000_007, 2)]
+        );
+        let sieve = primal::Sieve::new(10_000);
+        for n in 1..10_000u64 {
+            let expected: Vec<(u64, usize)> = sieve
+                .factor(n as usize)
+                .unwrap()
+                .iter()
+                .map(|&(p, e)| (p as u64, e))
+                .collect();
+            assert_eq!(factor(n), expected);
+        }
+    }
+}
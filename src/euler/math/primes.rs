@@ -0,0 +1,486 @@
+//! Prime Numbers
+//!
+//! An odd-only, bit-packed, segmented [Sieve of Eratosthenes].
+//!
+//! Only the odd numbers are stored, one bit each, so a sieve up to 10^8 needs
+//! about 6MB.  The sieving is done one cache sized segment at a time, with the
+//! base primes up to the square root of the upper bound.
+//!
+//! Unlike the primal crate, the prime iterators here are double ended, so they
+//! can be reversed without collecting them into a vector first.
+//! For very large ranges (near 10^12) use [primes_between], which only
+//! keeps the base primes and one segment (per direction) in memory.
+//!
+//! [Sieve of Eratosthenes]: https://en.wikipedia.org/wiki/Sieve_of_Eratosthenes
+
+use num_integer::Roots;
+
+/// Number of odd numbers in each segment (32KB of bits)
+const SEGMENT_SIZE: usize = 1 << 18;
+
+/// Returns the odd primes up to and including n
+///
+/// A simple (non-segmented) sieve used to create the base primes
+/// for the segmented sieve.  n is never more than 10^6 or so.
+fn odd_primes_to(n: usize) -> Vec<usize> {
+    let mut primes = Vec::new();
+    if n < 3 {
+        return primes;
+    }
+    // index i is the odd number 2i+1
+    let mut composite = vec![false; n / 2 + 1];
+    for i in 1..=(n - 1) / 2 {
+        if composite[i] {
+            continue;
+        }
+        let p = 2 * i + 1;
+        primes.push(p);
+        let mut j = p * p / 2;
+        while j < composite.len() {
+            composite[j] = true;
+            j += p;
+        }
+    }
+    primes
+}
+
+/// Returns the bits for the odd numbers start, start+2, .. start+2(len-1)
+///
+/// start must be odd.  A set bit is a composite number.
+/// The bit for 1 is set, so callers do not need to special case it.
+/// base must contain all the odd primes up to the square root of the
+/// largest number in the segment.
+fn sieve_segment(base: &[usize], start: usize, len: usize) -> Vec<u64> {
+    let mut bits = vec![0u64; len.div_ceil(64)];
+    let end = start + 2 * len; // exclusive
+    for &p in base {
+        let p2 = p * p;
+        if p2 >= end {
+            break;
+        }
+        // first odd multiple of p that is not less than start (or p^2)
+        let first = if p2 >= start {
+            p2
+        } else {
+            let m = start.div_ceil(p) * p;
+            if m.is_multiple_of(2) {
+                m + p
+            } else {
+                m
+            }
+        };
+        let mut i = (first - start) / 2;
+        while i < len {
+            bits[i / 64] |= 1 << (i % 64);
+            i += p;
+        }
+    }
+    if start == 1 {
+        bits[0] |= 1;
+    }
+    // Mark the unused bits at the end as composite
+    if !len.is_multiple_of(64) {
+        bits[len / 64] |= !0 << (len % 64);
+    }
+    bits
+}
+
+/// A prime sieve of all numbers up to (and including) an upper bound
+///
+/// Use this when many is_prime() queries, or repeated iteration over the
+/// primes, are needed.
+pub struct Sieve {
+    limit: usize,
+    // bit i is the odd number 2i+1; a set bit is a composite number
+    bits: Vec<u64>,
+}
+
+impl Sieve {
+    /// Returns a sieve of the primes up to and including limit
+    ///
+    /// # Examples
+    /// ```
+    /// let sieve = Sieve::new(100);
+    /// assert!(sieve.is_prime(97));
+    /// ```
+    pub fn new(limit: usize) -> Sieve {
+        let odds = limit.div_ceil(2); // odd numbers 1..=limit
+        let base = odd_primes_to(limit.sqrt());
+        let mut bits = Vec::with_capacity(odds.div_ceil(64));
+        let mut i = 0;
+        while i < odds {
+            let len = SEGMENT_SIZE.min(odds - i);
+            bits.extend(sieve_segment(&base, 2 * i + 1, len));
+            i += len;
+        }
+        Sieve { limit, bits }
+    }
+
+    /// The largest number in the sieve
+    #[allow(dead_code)]
+    pub fn upper_bound(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns true if n is prime
+    ///
+    /// # Panics
+    ///
+    /// will panic if n is greater than the upper bound of the sieve
+    #[allow(dead_code)]
+    pub fn is_prime(&self, n: usize) -> bool {
+        assert!(n <= self.limit, "{} is beyond the sieve limit", n);
+        if n.is_multiple_of(2) {
+            return n == 2;
+        }
+        let i = n / 2;
+        self.bits[i / 64] & (1 << (i % 64)) == 0
+    }
+
+    /// Returns the number of primes less than or equal to n
+    ///
+    /// # Examples
+    /// ```
+    /// let sieve = Sieve::new(100);
+    /// assert_eq!(sieve.prime_pi(100), 25);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// will panic if n is greater than the upper bound of the sieve
+    #[allow(dead_code)]
+    pub fn prime_pi(&self, n: usize) -> usize {
+        assert!(n <= self.limit, "{} is beyond the sieve limit", n);
+        if n < 2 {
+            return 0;
+        }
+        // count the zero bits for the odd numbers 1..=n, and add 1 for the prime 2
+        let odds = n.div_ceil(2);
+        let words = odds / 64;
+        let mut count: usize = self.bits[..words]
+            .iter()
+            .map(|w| w.count_zeros() as usize)
+            .sum();
+        let rem = odds % 64;
+        if rem != 0 {
+            let mask = (1u64 << rem) - 1;
+            count += (!self.bits[words] & mask).count_ones() as usize;
+        }
+        count + 1
+    }
+
+    /// Returns a double ended iterator over all the primes in the sieve
+    ///
+    /// # Examples
+    /// ```
+    /// let sieve = Sieve::new(20);
+    /// assert_eq!(sieve.primes().rev().next(), Some(19));
+    /// ```
+    pub fn primes(&self) -> SievePrimes<'_> {
+        self.primes_between(0, self.limit)
+    }
+
+    /// Returns a double ended iterator over the primes p where a <= p <= b
+    ///
+    /// # Panics
+    ///
+    /// will panic if b is greater than the upper bound of the sieve
+    pub fn primes_between(&self, a: usize, b: usize) -> SievePrimes<'_> {
+        assert!(b <= self.limit, "{} is beyond the sieve limit", b);
+        SievePrimes {
+            sieve: self,
+            front: a,
+            back: b,
+        }
+    }
+}
+
+/// A double ended iterator over the primes in a Sieve
+///
+/// front and back are the (inclusive) bounds of the numbers still to be checked.
+pub struct SievePrimes<'a> {
+    sieve: &'a Sieve,
+    front: usize,
+    back: usize,
+}
+
+impl<'a> Iterator for SievePrimes<'a> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        if self.front > self.back {
+            return None;
+        }
+        if self.front <= 2 {
+            // 2 is the only even prime, after that, only check odd numbers
+            self.front = 3;
+            if self.back >= 2 {
+                return Some(2);
+            }
+            return None;
+        }
+        let bits = &self.sieve.bits;
+        let mut i = self.front / 2;
+        let last = self.back.saturating_sub(1) / 2;
+        while i <= last {
+            let word = !bits[i / 64] >> (i % 64);
+            if word == 0 {
+                i = (i / 64 + 1) * 64;
+                continue;
+            }
+            i += word.trailing_zeros() as usize;
+            if i > last {
+                break;
+            }
+            let p = 2 * i + 1;
+            self.front = p + 1;
+            return Some(p);
+        }
+        self.front = self.back + 1;
+        None
+    }
+}
+
+impl<'a> DoubleEndedIterator for SievePrimes<'a> {
+    fn next_back(&mut self) -> Option<usize> {
+        if self.front > self.back {
+            return None;
+        }
+        if self.back >= 3 {
+            let bits = &self.sieve.bits;
+            let first = (self.front / 2).max(1);
+            // index of the odd number at or below back
+            let mut i = (self.back - 1) / 2;
+            while i >= first {
+                // shift out the bits above i
+                let word = !bits[i / 64] << (63 - i % 64);
+                if word != 0 {
+                    let j = i - word.leading_zeros() as usize;
+                    if j < first {
+                        break;
+                    }
+                    let p = 2 * j + 1;
+                    self.back = p - 1;
+                    return Some(p);
+                }
+                if i < 64 {
+                    break;
+                }
+                i = i - i % 64 - 1;
+            }
+        }
+        // only 2 may be left
+        let found = self.front <= 2 && self.back >= 2;
+        self.front = self.back + 1;
+        if found {
+            Some(2)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns a double ended iterator over the primes p where a <= p <= b
+///
+/// This does not create a full sieve, so it can be used for ranges of large
+/// numbers (e.g. near 10^12).  Only the base primes up to the square root of b,
+/// and a segment for each end of the iterator are kept in memory.
+///
+/// # Examples
+/// ```
+/// let primes: Vec<usize> = primes_between(1_000_000_000_000, 1_000_000_000_100).collect();
+/// assert_eq!(primes, vec![1_000_000_000_039, 1_000_000_000_061, 1_000_000_000_063, 1_000_000_000_091]);
+/// ```
+#[allow(dead_code)]
+pub fn primes_between(a: usize, b: usize) -> PrimesBetween {
+    PrimesBetween {
+        base: odd_primes_to(b.sqrt()),
+        front: a,
+        back: b,
+        front_segment: Segment::empty(),
+        back_segment: Segment::empty(),
+    }
+}
+
+/// A block of sieved odd numbers
+struct Segment {
+    start: usize,
+    len: usize,
+    bits: Vec<u64>,
+}
+
+impl Segment {
+    fn empty() -> Segment {
+        Segment {
+            start: 1,
+            len: 0,
+            bits: Vec::new(),
+        }
+    }
+
+    /// n must be odd
+    fn contains(&self, n: usize) -> bool {
+        n >= self.start && (n - self.start) / 2 < self.len
+    }
+
+    /// n must be odd and in the segment
+    fn is_prime(&self, n: usize) -> bool {
+        let i = (n - self.start) / 2;
+        self.bits[i / 64] & (1 << (i % 64)) == 0
+    }
+}
+
+/// A double ended iterator over the primes in a range, see [primes_between]
+///
+/// front and back are the (inclusive) bounds of the numbers still to be checked.
+pub struct PrimesBetween {
+    base: Vec<usize>,
+    front: usize,
+    back: usize,
+    front_segment: Segment,
+    back_segment: Segment,
+}
+
+impl Iterator for PrimesBetween {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        while self.front <= self.back {
+            let n = self.front;
+            if n < 3 || n.is_multiple_of(2) {
+                self.front += 1;
+                if n == 2 {
+                    return Some(2);
+                }
+                continue;
+            }
+            if !self.front_segment.contains(n) {
+                let len = SEGMENT_SIZE.min((self.back - n) / 2 + 1);
+                self.front_segment = Segment {
+                    start: n,
+                    len,
+                    bits: sieve_segment(&self.base, n, len),
+                };
+            }
+            self.front += 2;
+            if self.front_segment.is_prime(n) {
+                return Some(n);
+            }
+        }
+        None
+    }
+}
+
+impl DoubleEndedIterator for PrimesBetween {
+    fn next_back(&mut self) -> Option<usize> {
+        while self.front <= self.back {
+            let n = self.back;
+            if n < 3 {
+                // stop before back underflows
+                self.front = n + 1;
+                if n == 2 {
+                    return Some(2);
+                }
+                continue;
+            }
+            if n.is_multiple_of(2) {
+                self.back -= 1;
+                continue;
+            }
+            if !self.back_segment.contains(n) {
+                let lowest = self.front.max(3) | 1;
+                let len = SEGMENT_SIZE.min((n - lowest) / 2 + 1);
+                let start = n - 2 * (len - 1);
+                self.back_segment = Segment {
+                    start,
+                    len,
+                    bits: sieve_segment(&self.base, start, len),
+                };
+            }
+            // step down to the even number, so that 2 is not skipped
+            self.back -= 1;
+            if self.back_segment.is_prime(n) {
+                return Some(n);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMES_TO_50: [usize; 15] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
+
+    #[test]
+    pub fn sieve_primes_test() {
+        let sieve = Sieve::new(50);
+        assert_eq!(sieve.primes().collect::<Vec<_>>(), PRIMES_TO_50.to_vec());
+        let mut rev: Vec<usize> = sieve.primes().rev().collect();
+        rev.reverse();
+        assert_eq!(rev, PRIMES_TO_50.to_vec());
+    }
+    #[test]
+    pub fn sieve_primes_between_test() {
+        let sieve = Sieve::new(1000);
+        let primes: Vec<usize> = sieve.primes_between(2, 7).collect();
+        assert_eq!(primes, vec![2, 3, 5, 7]);
+        let primes: Vec<usize> = sieve.primes_between(8, 10).collect();
+        assert!(primes.is_empty());
+        let primes: Vec<usize> = sieve.primes_between(900, 1000).rev().collect();
+        assert_eq!(primes[0], 997);
+        assert_eq!(*primes.last().unwrap(), 907);
+    }
+    #[test]
+    pub fn sieve_matches_primal_test() {
+        let n = 1_000_000;
+        let sieve = Sieve::new(n);
+        let primal = primal::Sieve::new(n);
+        assert!(sieve.primes().eq(primal.primes_from(0)));
+        assert_eq!(sieve.prime_pi(n), 78498);
+        assert_eq!(sieve.prime_pi(999_983), 78498);
+        assert_eq!(sieve.prime_pi(999_982), 78497);
+        assert!((0..2000).all(|i| sieve.is_prime(i) == primal.is_prime(i)));
+    }
+    #[test]
+    pub fn double_ended_test() {
+        let sieve = Sieve::new(100);
+        let mut primes = sieve.primes();
+        assert_eq!(primes.next(), Some(2));
+        assert_eq!(primes.next_back(), Some(97));
+        let middle: Vec<usize> = primes.collect();
+        assert_eq!(middle.len(), 23);
+    }
+    #[test]
+    pub fn primes_between_test() {
+        let primes: Vec<usize> = primes_between(0, 50).collect();
+        assert_eq!(primes, PRIMES_TO_50.to_vec());
+        let mut rev: Vec<usize> = primes_between(0, 50).rev().collect();
+        rev.reverse();
+        assert_eq!(rev, PRIMES_TO_50.to_vec());
+        let sieve = Sieve::new(2_000_000);
+        assert!(primes_between(1_000, 2_000_000).eq(sieve.primes_between(1_000, 2_000_000)));
+        assert!(primes_between(1_000, 2_000_000)
+            .rev()
+            .eq(sieve.primes_between(1_000, 2_000_000).rev()));
+    }
+    #[test]
+    pub fn primes_between_large_test() {
+        let primes: Vec<usize> = primes_between(1_000_000_000_000, 1_000_000_000_100).collect();
+        assert_eq!(
+            primes,
+            vec![
+                1_000_000_000_039,
+                1_000_000_000_061,
+                1_000_000_000_063,
+                1_000_000_000_091
+            ]
+        );
+        let mut primes = primes_between(1_000_000_000_000, 1_000_000_000_100);
+        assert_eq!(primes.next_back(), Some(1_000_000_000_091));
+        assert_eq!(primes.next(), Some(1_000_000_000_039));
+        assert_eq!(primes.next_back(), Some(1_000_000_000_063));
+        assert_eq!(primes.next(), Some(1_000_000_000_061));
+        assert_eq!(primes.next(), None);
+        assert_eq!(primes.next_back(), None);
+    }
+}
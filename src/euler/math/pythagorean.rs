@@ -0,0 +1,452 @@
+//! Pythagorean Triples
+//!
+//! A Pythagorean triple (a, b, c) has a^2 + b^2 = c^2.  It is primitive if
+//! a, b and c are coprime, and every other triple is a multiple of a primitive.
+//!
+//! There are two ways to generate all the primitive triples:
+//!
+//! * [Euclid's formula]: for m > n > 0, coprime and not both odd,
+//!
+//!       a = m^2 - n^2,  b = 2mn,  c = m^2 + n^2
+//!
+//!   generates every primitive triple exactly once.
+//! * The [Berggren tree]: every primitive triple is a descendant of (3, 4, 5),
+//!   where each triple has three children found by multiplying by a fixed matrix.
+//!   Each child is bigger than its parent, so a branch can be pruned as soon as
+//!   it exceeds the bound.
+//!
+//! Both generators produce the same triples, in a different order, with the
+//! legs ordered so that a < b.
+//!
+//! The triples with a given hypotenuse c are the Gaussian integers a + bi with
+//! norm c^2, so they are found (and counted) from the factorization of c.
+//!
+//! [Euclid's formula]: https://en.wikipedia.org/wiki/Pythagorean_triple#Generating_a_triple
+//! [Berggren tree]: https://en.wikipedia.org/wiki/Tree_of_primitive_Pythagorean_triples
+
+use super::{primality, GaussianInt};
+use num_integer::{gcd, Roots};
+
+/// A Pythagorean triple with legs a < b and hypotenuse c
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Triple {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+}
+
+impl Triple {
+    /// Returns the triple with the legs in order
+    fn new(a: u64, b: u64, c: u64) -> Triple {
+        Triple {
+            a: a.min(b),
+            b: a.max(b),
+            c,
+        }
+    }
+
+    /// Returns the triple from Euclid's formula for m and n
+    ///
+    /// The triple is primitive when m and n are coprime and not both odd.
+    ///
+    /// # Examples
+    /// ```
+    /// assert_eq!(Triple::from_parameters(2, 1), Triple { a: 3, b: 4, c: 5 });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// will panic unless m > n > 0
+    pub fn from_parameters(m: u64, n: u64) -> Triple {
+        assert!(m > n && n > 0, "Euclid's formula needs m > n > 0");
+        Triple::new(m * m - n * n, 2 * m * n, m * m + n * n)
+    }
+
+    /// Returns (m, n, k) where self is k times the primitive triple for (m, n)
+    ///
+    /// # Examples
+    /// ```
+    /// assert_eq!(Triple { a: 9, b: 12, c: 15 }.parameters(), (2, 1, 3));
+    /// ```
+    #[allow(dead_code)]
+    pub fn parameters(self) -> (u64, u64, u64) {
+        let k = gcd(self.a, self.b);
+        let (a, b, c) = (self.a / k, self.b / k, self.c / k);
+        // the odd leg is m^2 - n^2, so m^2 = (c + odd)/2 and n^2 = (c - odd)/2
+        let odd = if a % 2 == 1 { a } else { b };
+        (((c + odd) / 2).sqrt(), ((c - odd) / 2).sqrt(), k)
+    }
+
+    /// Returns a + b + c
+    pub fn perimeter(self) -> u64 {
+        self.a + self.b + self.c
+    }
+
+    /// Returns true if a, b and c are coprime
+    #[allow(dead_code)]
+    pub fn is_primitive(self) -> bool {
+        gcd(self.a, self.b) == 1
+    }
+
+    /// Returns the triple (ka, kb, kc)
+    pub fn scale(self, k: u64) -> Triple {
+        Triple {
+            a: k * self.a,
+            b: k * self.b,
+            c: k * self.c,
+        }
+    }
+
+    /// Returns the three children of self in the Berggren tree
+    fn children(self) -> [Triple; 3] {
+        let (a, b, c) = (self.a as i128, self.b as i128, self.c as i128);
+        let child = |a: i128, b: i128, c: i128| Triple::new(a as u64, b as u64, c as u64);
+        [
+            child(a - 2 * b + 2 * c, 2 * a - b + 2 * c, 2 * a - 2 * b + 3 * c),
+            child(a + 2 * b + 2 * c, 2 * a + b + 2 * c, 2 * a + 2 * b + 3 * c),
+            child(
+                -a + 2 * b + 2 * c,
+                -2 * a + b + 2 * c,
+                -2 * a + 2 * b + 3 * c,
+            ),
+        ]
+    }
+}
+
+/// The limit on the triples to generate (the limits are inclusive)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    /// a + b + c is at most the limit
+    Perimeter(u64),
+    /// c is at most the limit
+    #[allow(dead_code)]
+    Hypotenuse(u64),
+    /// both legs are at most the limit
+    #[allow(dead_code)]
+    Leg(u64),
+}
+
+impl Bound {
+    /// Returns true if the triple is within the bound
+    pub fn contains(self, t: &Triple) -> bool {
+        match self {
+            Bound::Perimeter(p) => t.perimeter() <= p,
+            Bound::Hypotenuse(h) => t.c <= h,
+            Bound::Leg(l) => t.b <= l,
+        }
+    }
+
+    /// Returns false if no triple with a hypotenuse of at least t.c is within
+    /// the bound.  The Berggren children of t all have a larger hypotenuse and
+    /// perimeter.
+    fn may_contain_descendants(self, t: &Triple) -> bool {
+        match self {
+            Bound::Leg(l) => {
+                // the longer leg is at least c/√2
+                let c = t.c as u128;
+                c * c <= 2 * l as u128 * l as u128
+            }
+            _ => self.contains(t),
+        }
+    }
+
+    /// Returns true if all the (m, n) with n < m are outside the bound
+    fn excludes_all_m(self, m: u64) -> bool {
+        let m = m as u128;
+        match self {
+            Bound::Perimeter(p) => 2 * m * (m + 1) > p as u128,
+            Bound::Hypotenuse(h) => m * m + 1 > h as u128,
+            // the longer leg is at least c/√2 > m^2/√2
+            Bound::Leg(l) => m * m * m * m > 2 * l as u128 * l as u128,
+        }
+    }
+
+    /// Returns true if (m, n) and all (m, n') with n' > n are outside the bound
+    fn excludes_n(self, m: u64, n: u64) -> bool {
+        let (m, n) = (m as u128, n as u128);
+        match self {
+            Bound::Perimeter(p) => 2 * m * (m + n) > p as u128,
+            Bound::Hypotenuse(h) => m * m + n * n > h as u128,
+            Bound::Leg(l) => 2 * m * n > l as u128,
+        }
+    }
+}
+
+/// An iterator over the primitive triples from Euclid's formula
+pub struct EuclidTriples {
+    bound: Bound,
+    m: u64,
+    n: u64,
+}
+
+impl EuclidTriples {
+    /// The current (m, n) parameters, i.e. those of the last triple returned
+    #[allow(dead_code)]
+    pub fn parameters(&self) -> (u64, u64) {
+        (self.m, self.n)
+    }
+}
+
+impl Iterator for EuclidTriples {
+    type Item = Triple;
+    fn next(&mut self) -> Option<Triple> {
+        loop {
+            self.n += 1;
+            if self.n >= self.m || self.bound.excludes_n(self.m, self.n) {
+                self.m += 1;
+                self.n = 0;
+                if self.bound.excludes_all_m(self.m) {
+                    return None;
+                }
+                continue;
+            }
+            if (self.m - self.n) % 2 == 1 && gcd(self.m, self.n) == 1 {
+                let t = Triple::from_parameters(self.m, self.n);
+                if self.bound.contains(&t) {
+                    return Some(t);
+                }
+            }
+        }
+    }
+}
+
+/// Returns an iterator over the primitive triples within the bound, using Euclid's formula
+///
+/// The triples are ordered by m, then n.
+///
+/// # Examples
+/// ```
+/// let t: Vec<Triple> = primitive_triples(Bound::Hypotenuse(20)).collect();
+/// assert_eq!(t.len(), 3);  // (3,4,5), (5,12,13), (8,15,17)
+/// ```
+pub fn primitive_triples(bound: Bound) -> EuclidTriples {
+    EuclidTriples { bound, m: 1, n: 0 }
+}
+
+/// An iterator over the primitive triples in the Berggren tree
+#[allow(dead_code)]
+pub struct BerggrenTriples {
+    bound: Bound,
+    stack: Vec<Triple>,
+}
+
+impl Iterator for BerggrenTriples {
+    type Item = Triple;
+    fn next(&mut self) -> Option<Triple> {
+        while let Some(t) = self.stack.pop() {
+            for child in t.children().iter() {
+                if self.bound.may_contain_descendants(child) {
+                    self.stack.push(*child);
+                }
+            }
+            if self.bound.contains(&t) {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+/// Returns an iterator over the primitive triples within the bound, using the Berggren tree
+///
+/// The tree is searched depth first.
+#[allow(dead_code)]
+pub fn berggren_triples(bound: Bound) -> BerggrenTriples {
+    let root = Triple { a: 3, b: 4, c: 5 };
+    let mut stack = Vec::new();
+    if bound.may_contain_descendants(&root) {
+        stack.push(root);
+    }
+    BerggrenTriples { bound, stack }
+}
+
+/// Returns an iterator over all the triples (primitive and multiples) within the bound
+///
+/// Each primitive triple is followed by its multiples.
+///
+/// # Examples
+/// ```
+/// let t: Vec<Triple> = triples(Bound::Perimeter(30)).collect();
+/// assert_eq!(t.len(), 3);  // (3,4,5), (6,8,10), (5,12,13)
+/// ```
+#[allow(dead_code)]
+pub fn triples(bound: Bound) -> impl Iterator<Item = Triple> {
+    primitive_triples(bound).flat_map(move |t| {
+        (1..)
+            .map(move |k| t.scale(k))
+            .take_while(move |t| bound.contains(t))
+    })
+}
+
+/// Returns a vector v where v[p] is the number of right triangles with perimeter p
+///
+/// # Examples
+/// ```
+/// // Euler 39: 120 has three solutions
+/// assert_eq!(perimeter_counts(120)[120], 3);
+/// ```
+#[allow(dead_code)]
+pub fn perimeter_counts(limit: u64) -> Vec<u32> {
+    let mut counts = vec![0; limit as usize + 1];
+    for t in primitive_triples(Bound::Perimeter(limit)) {
+        let p = t.perimeter() as usize;
+        for multiple in (p..=limit as usize).step_by(p) {
+            counts[multiple] += 1;
+        }
+    }
+    counts
+}
+
+/// Returns the number of right triangles with a leg of length l
+///
+/// The other leg b and the hypotenuse c satisfy (c-b)(c+b) = l^2, so each
+/// factorization l^2 = d * e with d < e and d, e of the same parity is a
+/// triangle.  If l = 2^k * r with r odd, counting these gives
+///
+/// * (d(l^2) - 1) / 2 when l is odd
+/// * ((2k - 1) * d(r^2) - 1) / 2 when l is even
+///
+/// where d() is the number of divisors.
+///
+/// # Examples
+/// ```
+/// // (12, 5, 13), (12, 9, 15), (12, 16, 20), (12, 35, 37)
+/// assert_eq!(leg_count(12), 4);
+/// ```
+#[allow(dead_code)]
+pub fn leg_count(l: u64) -> u64 {
+    if l == 0 {
+        return 0;
+    }
+    let k = l.trailing_zeros() as u64;
+    let odd_divisors: u64 = primality::factor(l >> k)
+        .iter()
+        .map(|&(_, e)| 2 * e as u64 + 1)
+        .product();
+    if k == 0 {
+        (odd_divisors - 1) / 2
+    } else {
+        ((2 * k - 1) * odd_divisors - 1) / 2
+    }
+}
+
+/// Returns the right triangles with hypotenuse c, in order of the shorter leg
+///
+/// Each triple is a Gaussian integer a + bi with norm c^2 (see
+/// GaussianInt::with_norm), with both legs positive.  Both a + bi and b + ai
+/// are found, so only the one with a < b is kept.
+///
+/// # Examples
+/// ```
+/// // 25^2 = 7^2 + 24^2 = 15^2 + 20^2
+/// let t = hypotenuse_triples(25);
+/// assert_eq!(t, vec![Triple { a: 7, b: 24, c: 25 }, Triple { a: 15, b: 20, c: 25 }]);
+/// ```
+///
+/// # Panics
+///
+/// will panic if c^2 overflows a u64
+#[allow(dead_code)]
+pub fn hypotenuse_triples(c: u64) -> Vec<Triple> {
+    let c2 = c.checked_mul(c).expect("c^2 overflows a u64");
+    GaussianInt::with_norm(c2)
+        .into_iter()
+        .filter(|z| 0 < z.re && z.re < z.im)
+        .map(|z| Triple::new(z.re as u64, z.im as u64, c))
+        .collect()
+}
+
+/// Returns the number of right triangles with hypotenuse c
+///
+/// There are Π (2e + 1) Gaussian integers with norm c^2 in the first quadrant,
+/// over the primes p^e in c with p = 1 (mod 4).  One of them is c itself, and
+/// the rest are the triples, each found twice, as a + bi and b + ai.
+///
+/// # Examples
+/// ```
+/// assert_eq!(hypotenuse_count(25), 2);
+/// ```
+#[allow(dead_code)]
+pub fn hypotenuse_count(c: u64) -> u64 {
+    let points: u64 = primality::factor(c)
+        .iter()
+        .filter(|&&(p, _)| p % 4 == 1)
+        .map(|&(_, e)| 2 * e as u64 + 1)
+        .product();
+    (points - 1) / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(bound: Bound, limit: u64) -> Vec<Triple> {
+        let mut v = Vec::new();
+        for a in 1..limit {
+            for b in a + 1..limit {
+                let c = (a * a + b * b).sqrt();
+                let t = Triple { a, b, c };
+                if c * c == a * a + b * b && bound.contains(&t) {
+                    v.push(t);
+                }
+            }
+        }
+        v
+    }
+
+    fn sorted(iter: impl Iterator<Item = Triple>) -> Vec<Triple> {
+        let mut v: Vec<Triple> = iter.collect();
+        v.sort_unstable();
+        v
+    }
+
+    #[test]
+    pub fn triples_test() {
+        for &bound in [
+            Bound::Perimeter(500),
+            Bound::Hypotenuse(200),
+            Bound::Leg(150),
+            Bound::Leg(4),
+            Bound::Perimeter(11),
+        ]
+        .iter()
+        {
+            let all = brute_force(bound, 250);
+            let primitive: Vec<Triple> = all.iter().cloned().filter(|t| t.is_primitive()).collect();
+            assert_eq!(sorted(triples(bound)), all);
+            assert_eq!(sorted(primitive_triples(bound)), primitive);
+            assert_eq!(sorted(berggren_triples(bound)), primitive);
+        }
+    }
+    #[test]
+    pub fn parameters_test() {
+        let mut euclid = primitive_triples(Bound::Hypotenuse(1000));
+        while let Some(t) = euclid.next() {
+            let (m, n) = euclid.parameters();
+            assert_eq!(t.parameters(), (m, n, 1));
+            assert_eq!(t.scale(7).parameters(), (m, n, 7));
+        }
+    }
+    #[test]
+    pub fn counts_test() {
+        let counts = perimeter_counts(1000);
+        // Euler 39, the perimeter <= 1000 with the most solutions
+        let best = (0..=1000).max_by_key(|&p| counts[p]).unwrap();
+        assert_eq!(best, 840);
+        for l in 1..100 {
+            let expected = triples(Bound::Leg(l * l))
+                .filter(|t| t.a == l || t.b == l)
+                .count();
+            assert_eq!(leg_count(l), expected as u64, "{}", l);
+        }
+        for c in 1..300 {
+            let expected: Vec<Triple> = sorted(triples(Bound::Hypotenuse(c)).filter(|t| t.c == c));
+            assert_eq!(hypotenuse_triples(c), expected, "{}", c);
+            assert_eq!(hypotenuse_count(c), expected.len() as u64, "{}", c);
+        }
+        // 5^2 13^2 17^2 29^2 has (5^4 - 1)/2 triples
+        assert_eq!(hypotenuse_count(5 * 5 * 13 * 13 * 17 * 17 * 29 * 29), 312);
+        let c = 5 * 13 * 17 * 29 * 37;
+        assert_eq!(hypotenuse_triples(c).len() as u64, hypotenuse_count(c));
+    }
+}
@@ -0,0 +1,428 @@
+//! Rational Numbers
+//!
+//! A Ratio is an exact fraction p/q.  It is always kept in lowest terms with a
+//! positive denominator, so two equal ratios have the same numerator and
+//! denominator, and can be compared with ==.
+//!
+//! * The usual arithmetic operators, and ordering.  The intermediate products
+//!   are computed in i128, and reduced before being stored in an i64.
+//! * The mediant, and navigation of the [Stern–Brocot tree], which contains every
+//!   positive rational exactly once.
+//! * Conversion to and from [continued fractions].
+//! * [Farey sequences], the ratios in [0, 1] with denominator at most n, in order.
+//!
+//! See the crate [num_rational] for a more general solution.
+//!
+//! [Stern–Brocot tree]: https://en.wikipedia.org/wiki/Stern%E2%80%93Brocot_tree
+//! [continued fractions]: https://en.wikipedia.org/wiki/Continued_fraction
+//! [Farey sequences]: https://en.wikipedia.org/wiki/Farey_sequence
+//! [num_rational]: https://crates.io/crates/num-rational
+
+use num_integer::{gcd, Integer};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+
+/// An exact rational number numer/denom
+///
+/// # Examples
+/// ```
+/// let a = Ratio::new(1, 6);
+/// let b = Ratio::new(1, 3);
+/// assert_eq!(a + b, Ratio::new(1, 2));
+/// assert_eq!(Ratio::new(6, -4).to_string(), "-3/2");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ratio {
+    numer: i64,
+    denom: i64,
+}
+
+impl Ratio {
+    /// Returns numer/denom in lowest terms
+    ///
+    /// # Panics
+    ///
+    /// will panic if denom is zero
+    pub fn new(numer: i64, denom: i64) -> Ratio {
+        Ratio::reduce(numer as i128, denom as i128)
+    }
+
+    /// Returns the ratio n/1
+    pub fn from_integer(n: i64) -> Ratio {
+        Ratio { numer: n, denom: 1 }
+    }
+
+    /// Reduces numer/denom to lowest terms with a positive denominator
+    ///
+    /// # Panics
+    ///
+    /// will panic if denom is zero, or the reduced ratio does not fit in an i64
+    fn reduce(numer: i128, denom: i128) -> Ratio {
+        assert!(denom != 0, "denominator is zero");
+        let g = gcd(numer, denom);
+        let sign = denom.signum();
+        let numer = sign * numer / g;
+        let denom = sign * denom / g;
+        Ratio {
+            numer: i64::try_from(numer).expect("numerator overflow"),
+            denom: i64::try_from(denom).expect("denominator overflow"),
+        }
+    }
+
+    /// The numerator (which has the sign of the ratio)
+    pub fn numer(self) -> i64 {
+        self.numer
+    }
+
+    /// The denominator (which is always positive)
+    pub fn denom(self) -> i64 {
+        self.denom
+    }
+
+    /// Returns true if the denominator is 1
+    #[allow(dead_code)]
+    pub fn is_integer(self) -> bool {
+        self.denom == 1
+    }
+
+    /// Returns 1/self
+    ///
+    /// # Panics
+    ///
+    /// will panic if self is zero
+    pub fn recip(self) -> Ratio {
+        Ratio::new(self.denom, self.numer)
+    }
+
+    /// Returns the largest integer less than or equal to self
+    #[allow(dead_code)]
+    pub fn floor(self) -> i64 {
+        Integer::div_floor(&self.numer, &self.denom)
+    }
+
+    /// Returns the fractional part, self - floor(self), which is in [0, 1)
+    #[allow(dead_code)]
+    pub fn fract(self) -> Ratio {
+        Ratio {
+            numer: self.numer.mod_floor(&self.denom),
+            denom: self.denom,
+        }
+    }
+
+    /// Returns the mediant (a+c)/(b+d) of a/b and c/d
+    ///
+    /// The mediant is always between the two ratios.  It is not the average,
+    /// e.g. the mediant of 1/2 and 1/1 is 2/3.
+    #[allow(dead_code)]
+    pub fn mediant(self, other: Ratio) -> Ratio {
+        Ratio::new(self.numer + other.numer, self.denom + other.denom)
+    }
+
+    /// Returns the path from the root (1/1) of the Stern–Brocot tree to self
+    ///
+    /// The path is a string of 'L' and 'R' for the left and right branches.
+    /// Each step replaces one bound with the mediant of the bounds, starting
+    /// with 0/1 and 1/0.  The path is the run length encoding of the continued
+    /// fraction.
+    ///
+    /// # Examples
+    /// ```
+    /// assert_eq!(Ratio::new(3, 5).stern_brocot_path(), "LRL");
+    /// assert_eq!(Ratio::from_integer(1).stern_brocot_path(), "");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// will panic if self is not positive
+    #[allow(dead_code)]
+    pub fn stern_brocot_path(self) -> String {
+        assert!(self.numer > 0, "only positive ratios are in the tree");
+        let cf = self.to_continued_fraction();
+        let mut path = String::new();
+        for (i, &a) in cf.iter().enumerate() {
+            // the last term is one more than the run length
+            let run = if i == cf.len() - 1 { a - 1 } else { a };
+            let step = if i % 2 == 0 { 'R' } else { 'L' };
+            path.extend(std::iter::repeat_n(step, run as usize));
+        }
+        path
+    }
+
+    /// Returns the ratio at the end of a path in the Stern–Brocot tree
+    ///
+    /// The inverse of stern_brocot_path.
+    ///
+    /// # Examples
+    /// ```
+    /// assert_eq!(Ratio::from_stern_brocot_path("LRL"), Ratio::new(3, 5));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// will panic if the path contains a character other than 'L' or 'R'
+    #[allow(dead_code)]
+    pub fn from_stern_brocot_path(path: &str) -> Ratio {
+        // the bounds are kept as (numer, denom) tuples, since 1/0 is not a Ratio
+        let mut left = (0, 1);
+        let mut right = (1, 0);
+        let mut mediant = (1, 1);
+        for step in path.chars() {
+            match step {
+                'L' => right = mediant,
+                'R' => left = mediant,
+                _ => panic!("invalid step {} in Stern–Brocot path", step),
+            }
+            mediant = (left.0 + right.0, left.1 + right.1);
+        }
+        Ratio::new(mediant.0, mediant.1)
+    }
+
+    /// Returns the continued fraction [a0; a1, a2, ...] of self
+    ///
+    /// self = a0 + 1/(a1 + 1/(a2 + ...)), where a0 is the floor of self, and the
+    /// other terms are positive.  The last term is greater than 1 (except for
+    /// self = 1, which is [1]), so the expansion is unique.
+    ///
+    /// # Examples
+    /// ```
+    /// assert_eq!(Ratio::new(415, 93).to_continued_fraction(), vec![4, 2, 6, 7]);
+    /// ```
+    #[allow(dead_code)]
+    pub fn to_continued_fraction(self) -> Vec<i64> {
+        let mut terms = Vec::new();
+        let (mut p, mut q) = (self.numer, self.denom);
+        while q != 0 {
+            let (a, r) = p.div_mod_floor(&q);
+            terms.push(a);
+            (p, q) = (q, r);
+        }
+        terms
+    }
+
+    /// Returns the ratio with the continued fraction [a0; a1, a2, ...]
+    ///
+    /// # Examples
+    /// ```
+    /// assert_eq!(Ratio::from_continued_fraction(&[4, 2, 6, 7]), Ratio::new(415, 93));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// will panic if terms is empty
+    #[allow(dead_code)]
+    pub fn from_continued_fraction(terms: &[i64]) -> Ratio {
+        assert!(
+            !terms.is_empty(),
+            "a continued fraction needs at least one term"
+        );
+        // evaluate from the last term, as (numer, denom) of the tail
+        let last = terms.len() - 1;
+        let (p, q) = terms[..last]
+            .iter()
+            .rev()
+            .fold((terms[last] as i128, 1i128), |(p, q), &a| {
+                (a as i128 * p + q, p)
+            });
+        Ratio::reduce(p, q)
+    }
+}
+
+impl From<i64> for Ratio {
+    fn from(n: i64) -> Ratio {
+        Ratio::from_integer(n)
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.denom == 1 {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Ratio) -> Ordering {
+        // the denominators are positive, so cross multiplying keeps the order
+        (self.numer as i128 * other.denom as i128).cmp(&(other.numer as i128 * self.denom as i128))
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Ratio) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Ratio {
+    type Output = Ratio;
+    fn add(self, rhs: Ratio) -> Ratio {
+        let (a, b) = (self.numer as i128, self.denom as i128);
+        let (c, d) = (rhs.numer as i128, rhs.denom as i128);
+        Ratio::reduce(a * d + c * b, b * d)
+    }
+}
+
+impl Sub for Ratio {
+    type Output = Ratio;
+    fn sub(self, rhs: Ratio) -> Ratio {
+        self + (-rhs)
+    }
+}
+
+impl Neg for Ratio {
+    type Output = Ratio;
+    fn neg(self) -> Ratio {
+        Ratio {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+impl Mul for Ratio {
+    type Output = Ratio;
+    fn mul(self, rhs: Ratio) -> Ratio {
+        Ratio::reduce(
+            self.numer as i128 * rhs.numer as i128,
+            self.denom as i128 * rhs.denom as i128,
+        )
+    }
+}
+
+impl Div for Ratio {
+    type Output = Ratio;
+    /// # Panics
+    ///
+    /// will panic if rhs is zero
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Ratio) -> Ratio {
+        self * rhs.recip()
+    }
+}
+
+impl AddAssign for Ratio {
+    fn add_assign(&mut self, rhs: Ratio) {
+        *self = *self + rhs;
+    }
+}
+
+/// An iterator over the Farey sequence of order n
+///
+/// Each term is computed from the previous two, so no sorting is needed.
+/// If a/b and c/d are consecutive terms, the next term is (kc-a)/(kd-b)
+/// where k = (n+b)/d.
+pub struct Farey {
+    n: i64,
+    prev: (i64, i64),
+    next: Option<(i64, i64)>,
+}
+
+impl Iterator for Farey {
+    type Item = Ratio;
+    fn next(&mut self) -> Option<Ratio> {
+        let (c, d) = self.next?;
+        let (a, b) = self.prev;
+        self.prev = (c, d);
+        self.next = if c == d {
+            None
+        } else {
+            let k = (self.n + b) / d;
+            Some((k * c - a, k * d - b))
+        };
+        Some(Ratio { numer: c, denom: d })
+    }
+}
+
+/// Returns an iterator over the Farey sequence of order n
+///
+/// These are the fractions in lowest terms from 0/1 to 1/1, whose denominators
+/// are at most n, in ascending order.
+///
+/// # Examples
+/// ```
+/// let f: Vec<String> = farey(4).map(|r| r.to_string()).collect();
+/// assert_eq!(f, vec!["0", "1/4", "1/3", "1/2", "2/3", "3/4", "1"]);
+/// ```
+///
+/// # Panics
+///
+/// will panic if n is zero
+#[allow(dead_code)]
+pub fn farey(n: i64) -> Farey {
+    assert!(n > 0, "the order of a Farey sequence must be positive");
+    Farey {
+        n,
+        // the term before 0/1 would be -1/n, so that the formula gives 1/n next
+        prev: (-1, n),
+        next: Some((0, 1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn ratio_arithmetic_test() {
+        let a = Ratio::new(3, 4);
+        let b = Ratio::new(-5, 6);
+        assert_eq!(a + b, Ratio::new(-1, 12));
+        assert_eq!(a - b, Ratio::new(19, 12));
+        assert_eq!(a * b, Ratio::new(-5, 8));
+        assert_eq!(a / b, Ratio::new(-9, 10));
+        assert_eq!(Ratio::new(4, -8), Ratio::new(-1, 2));
+        assert_eq!(Ratio::new(0, -5), Ratio::from(0));
+        assert!(b < a && Ratio::new(1, 3) < Ratio::new(1, 2));
+        assert_eq!(Ratio::new(-7, 2).floor(), -4);
+        assert_eq!(Ratio::new(-7, 2).fract(), Ratio::new(1, 2));
+        let mut sum = Ratio::from(0);
+        for i in 1..=10 {
+            sum += Ratio::new(1, i * (i + 1));
+        }
+        assert_eq!(sum, Ratio::new(10, 11));
+    }
+    #[test]
+    pub fn continued_fraction_test() {
+        for p in -50..50 {
+            for q in 1..50 {
+                let r = Ratio::new(p, q);
+                let cf = r.to_continued_fraction();
+                assert!(cf[1..].iter().all(|&a| a > 0));
+                assert_eq!(Ratio::from_continued_fraction(&cf), r);
+            }
+        }
+        assert_eq!(Ratio::new(-8, 3).to_continued_fraction(), vec![-3, 3]);
+    }
+    #[test]
+    pub fn stern_brocot_test() {
+        assert_eq!(Ratio::new(1, 2).mediant(Ratio::from(1)), Ratio::new(2, 3));
+        assert_eq!(Ratio::new(3, 5).stern_brocot_path(), "LRL");
+        assert_eq!(Ratio::new(7, 2).stern_brocot_path(), "RRRL");
+        for p in 1..30 {
+            for q in 1..30 {
+                let r = Ratio::new(p, q);
+                assert_eq!(Ratio::from_stern_brocot_path(&r.stern_brocot_path()), r);
+            }
+        }
+    }
+    #[test]
+    pub fn farey_test() {
+        for n in 1..30 {
+            let f: Vec<Ratio> = farey(n).collect();
+            assert!(f.windows(2).all(|w| w[0] < w[1]));
+            // consecutive terms a/b < c/d are neighbours, bc - ad = 1
+            assert!(f
+                .windows(2)
+                .all(|w| w[1].numer() * w[0].denom() - w[0].numer() * w[1].denom() == 1));
+            let expected = (1..=n)
+                .map(|q| (0..=q).filter(|&p| gcd(p, q) == 1).count())
+                .sum::<usize>();
+            assert_eq!(f.len(), expected);
+        }
+    }
+}
@@ -0,0 +1,402 @@
+//! Linear Recurrences
+//!
+//! A linear recurrence of order k defines each term from the previous k terms,
+//!
+//!     a(n) = c1 a(n-1) + c2 a(n-2) + ... + ck a(n-k)
+//!
+//! starting from k seeds a(0), ..., a(k-1).  The Fibonacci iterator in
+//! fibonacci.rs is the case c = [1, 1] and seeds [0, 1], and the same
+//! iterator with other coefficients and seeds gives the Lucas numbers, Pell
+//! numbers, tribonacci numbers, etc.
+//!
+//! The terms can be any type with Add, Mul and From<u8>, e.g. u64, i64, u128
+//! or BigUint.  There is no overflow check other than the type's own, so a
+//! primitive type panics (in debug) at the first term that does not fit.
+//!
+//! A single term can be found without the ones before it, with
+//! [matrix exponentiation] of the companion matrix in O(k^3 log n) steps.
+//!
+//! Going the other way, berlekamp_massey() finds the shortest recurrence
+//! that produces a given list of terms.
+//!
+//! [matrix exponentiation]: https://en.wikipedia.org/wiki/Companion_matrix
+
+use super::Ratio;
+use std::ops::{Add, Mul};
+
+/// An iterator over the terms of a linear recurrence, starting at a(0)
+#[derive(Clone, Debug)]
+pub struct LinearRecurrence<T> {
+    coefficients: Vec<T>,
+    seeds: Vec<T>,
+    // the last k terms produced, oldest first
+    window: Vec<T>,
+    seeds_used: usize,
+}
+
+impl<T> LinearRecurrence<T>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T> + From<u8>,
+{
+    /// Returns the recurrence a(n) = c1 a(n-1) + ... + ck a(n-k) with seeds a(0), ..., a(k-1)
+    ///
+    /// # Examples
+    /// ```
+    /// // a(n) = 2 a(n-1) + 3 a(n-2)
+    /// let a: Vec<u64> = LinearRecurrence::new(vec![2, 3], vec![1, 1]).take(5).collect();
+    /// assert_eq!(a, vec![1, 1, 5, 13, 41]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// will panic if there are no coefficients, or if the number of seeds is different
+    pub fn new(coefficients: Vec<T>, seeds: Vec<T>) -> LinearRecurrence<T> {
+        assert!(!coefficients.is_empty(), "a recurrence needs coefficients");
+        assert_eq!(
+            coefficients.len(),
+            seeds.len(),
+            "a recurrence needs one seed per coefficient"
+        );
+        LinearRecurrence {
+            coefficients,
+            window: seeds.clone(),
+            seeds,
+            seeds_used: 0,
+        }
+    }
+
+    /// Returns the order of the recurrence, the number of previous terms used
+    #[allow(dead_code)]
+    pub fn order(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// Returns the term a(n), in O(k^3 log n) steps
+    ///
+    /// The state (a(n), ..., a(n+k-1)) is the companion matrix to the power n
+    /// times the seeds.  The matrix entries are terms of the sequence near
+    /// a(n+k), so a primitive type can overflow a few terms before a(n) does.
+    ///
+    /// # Examples
+    /// ```
+    /// assert_eq!(fibonacci::<u64>().term(90), 2880067194370816120);
+    /// ```
+    #[allow(dead_code)]
+    pub fn term(&self, n: u64) -> T {
+        let k = self.order();
+        if n < k as u64 {
+            return self.seeds[n as usize].clone();
+        }
+        let zero = T::from(0);
+        let one = T::from(1);
+        // each row of the companion matrix shifts the state up by one,
+        // except the last, which is the recurrence
+        let mut matrix = vec![vec![zero.clone(); k]; k];
+        for (i, row) in matrix.iter_mut().enumerate().take(k - 1) {
+            row[i + 1] = one.clone();
+        }
+        for (j, c) in self.coefficients.iter().enumerate() {
+            matrix[k - 1][k - 1 - j] = c.clone();
+        }
+        let power = matrix_pow(matrix, n, &zero, &one);
+        power[0]
+            .iter()
+            .zip(self.seeds.iter())
+            .fold(zero, |sum, (m, s)| sum + m.clone() * s.clone())
+    }
+}
+
+impl<T> Iterator for LinearRecurrence<T>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T>,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        // the seeds are produced first, so no term is computed before it is needed
+        if self.seeds_used < self.seeds.len() {
+            self.seeds_used += 1;
+            return Some(self.seeds[self.seeds_used - 1].clone());
+        }
+        let k = self.window.len();
+        let mut terms = self
+            .coefficients
+            .iter()
+            .zip(self.window.iter().rev())
+            .map(|(c, a)| c.clone() * a.clone());
+        let first = terms.next().unwrap();
+        let next = terms.fold(first, |sum, x| sum + x);
+        self.window.rotate_left(1);
+        self.window[k - 1] = next.clone();
+        Some(next)
+    }
+}
+
+/// Returns the product of two square matrices
+fn matrix_mul<T>(a: &[Vec<T>], b: &[Vec<T>], zero: &T) -> Vec<Vec<T>>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T>,
+{
+    let k = a.len();
+    (0..k)
+        .map(|i| {
+            (0..k)
+                .map(|j| {
+                    (0..k).fold(zero.clone(), |sum, l| {
+                        sum + a[i][l].clone() * b[l][j].clone()
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the matrix to the power exp, by repeated squaring
+fn matrix_pow<T>(matrix: Vec<Vec<T>>, exp: u64, zero: &T, one: &T) -> Vec<Vec<T>>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T>,
+{
+    let k = matrix.len();
+    let mut result: Vec<Vec<T>> = (0..k)
+        .map(|i| {
+            (0..k)
+                .map(|j| if i == j { one.clone() } else { zero.clone() })
+                .collect()
+        })
+        .collect();
+    let mut base = matrix;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = matrix_mul(&result, &base, zero);
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = matrix_mul(&base, &base, zero);
+        }
+    }
+    result
+}
+
+/// Returns the Fibonacci numbers 0, 1, 1, 2, 3, 5, ...
+///
+/// # Examples
+/// ```
+/// let f: Vec<u32> = fibonacci().take(7).collect();
+/// assert_eq!(f, vec![0, 1, 1, 2, 3, 5, 8]);
+/// ```
+#[allow(dead_code)]
+pub fn fibonacci<T>() -> LinearRecurrence<T>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T> + From<u8>,
+{
+    LinearRecurrence::new(vec![T::from(1), T::from(1)], vec![T::from(0), T::from(1)])
+}
+
+/// Returns the Lucas numbers 2, 1, 3, 4, 7, 11, ...
+///
+/// The Fibonacci recurrence, with seeds 2 and 1.  L(n) = F(n-1) + F(n+1).
+///
+/// # Examples
+/// ```
+/// let l: Vec<u32> = lucas().take(6).collect();
+/// assert_eq!(l, vec![2, 1, 3, 4, 7, 11]);
+/// ```
+#[allow(dead_code)]
+pub fn lucas<T>() -> LinearRecurrence<T>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T> + From<u8>,
+{
+    LinearRecurrence::new(vec![T::from(1), T::from(1)], vec![T::from(2), T::from(1)])
+}
+
+/// Returns the Pell numbers 0, 1, 2, 5, 12, 29, ...
+///
+/// P(n) = 2 P(n-1) + P(n-2).  The ratios P(n+1)/P(n) - 1 are the convergents of √2.
+///
+/// # Examples
+/// ```
+/// let p: Vec<u32> = pell().take(6).collect();
+/// assert_eq!(p, vec![0, 1, 2, 5, 12, 29]);
+/// ```
+#[allow(dead_code)]
+pub fn pell<T>() -> LinearRecurrence<T>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T> + From<u8>,
+{
+    LinearRecurrence::new(vec![T::from(2), T::from(1)], vec![T::from(0), T::from(1)])
+}
+
+/// Returns the tribonacci numbers 0, 0, 1, 1, 2, 4, 7, 13, ...
+///
+/// Each term is the sum of the previous three.
+///
+/// # Examples
+/// ```
+/// let t: Vec<u32> = tribonacci().take(8).collect();
+/// assert_eq!(t, vec![0, 0, 1, 1, 2, 4, 7, 13]);
+/// ```
+#[allow(dead_code)]
+pub fn tribonacci<T>() -> LinearRecurrence<T>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T> + From<u8>,
+{
+    let one = T::from(1);
+    LinearRecurrence::new(
+        vec![one.clone(), one.clone(), one.clone()],
+        vec![T::from(0), T::from(0), one],
+    )
+}
+
+/// Returns the coefficients of the shortest linear recurrence that produces terms
+///
+/// The [Berlekamp-Massey algorithm] builds the recurrence one term at a time,
+/// correcting it with an earlier version whenever it predicts the next term
+/// wrongly.  The result [c1, ..., cL] satisfies
+/// a(n) = c1 a(n-1) + ... + cL a(n-L) for every n from L to the end of terms.
+///
+/// The recurrence is only certain to be the right one if there are at least
+/// 2L terms.  The arithmetic is exact, with Ratio, so the coefficients may be
+/// fractions when the terms do not come from an integer recurrence.
+///
+/// # Examples
+/// ```
+/// let c = berlekamp_massey(&[0, 1, 1, 2, 3, 5, 8, 13]);
+/// assert_eq!(c, vec![Ratio::from(1), Ratio::from(1)]);
+/// ```
+///
+/// # Panics
+///
+/// will panic if a fraction overflows an i64
+///
+/// [Berlekamp-Massey algorithm]: https://en.wikipedia.org/wiki/Berlekamp%E2%80%93Massey_algorithm
+#[allow(dead_code)]
+pub fn berlekamp_massey(terms: &[i64]) -> Vec<Ratio> {
+    let zero = Ratio::from(0);
+    let terms: Vec<Ratio> = terms.iter().map(|&a| Ratio::from(a)).collect();
+    // the connection polynomials, current and before the last length change,
+    // where c[0] a(n) + c[1] a(n-1) + ... + c[L] a(n-L) = 0
+    let mut current = vec![Ratio::from(1)];
+    let mut previous = vec![Ratio::from(1)];
+    let mut length = 0;
+    let mut previous_discrepancy = Ratio::from(1);
+    let mut shift = 1;
+    for n in 0..terms.len() {
+        let discrepancy = (0..=length).fold(zero, |d, i| d + current[i] * terms[n - i]);
+        if discrepancy == zero {
+            shift += 1;
+            continue;
+        }
+        let scale = discrepancy / previous_discrepancy;
+        let mut corrected = current.clone();
+        if corrected.len() < previous.len() + shift {
+            corrected.resize(previous.len() + shift, zero);
+        }
+        for (i, &p) in previous.iter().enumerate() {
+            corrected[i + shift] = corrected[i + shift] - scale * p;
+        }
+        if 2 * length <= n {
+            length = n + 1 - length;
+            previous = std::mem::replace(&mut current, corrected);
+            previous_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            current = corrected;
+            shift += 1;
+        }
+    }
+    current.resize(length + 1, zero);
+    current[1..].iter().map(|&c| -c).collect()
+}
+
+/// Returns the shortest integer linear recurrence that produces terms
+///
+/// Returns None if the shortest recurrence has fractional coefficients.
+/// The seeds are the first terms, so the recurrence produces terms exactly.
+///
+/// # Examples
+/// ```
+/// let r = from_terms(&[1, 3, 9, 27, 81]).unwrap();
+/// assert_eq!(r.term(10), 59049);
+/// ```
+#[allow(dead_code)]
+pub fn from_terms(terms: &[i64]) -> Option<LinearRecurrence<i64>> {
+    let coefficients = berlekamp_massey(terms);
+    if coefficients.is_empty() || !coefficients.iter().all(|c| c.is_integer()) {
+        return None;
+    }
+    let seeds = terms[..coefficients.len()].to_vec();
+    let coefficients = coefficients.iter().map(|c| c.numer()).collect();
+    Some(LinearRecurrence::new(coefficients, seeds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fibonacci as fib, BigUint};
+    use super::*;
+
+    #[test]
+    pub fn sequences_test() {
+        let f: Vec<usize> = fibonacci().take(94).collect();
+        assert_eq!(f, fib::fibonacci_first(94));
+        let f: Vec<usize> = fibonacci().skip(2).take(40).collect();
+        let g: Vec<usize> = fib::iter().take(40).collect();
+        assert_eq!(f, g);
+        let l: Vec<u64> = lucas().take(10).collect();
+        assert_eq!(l, vec![2, 1, 3, 4, 7, 11, 18, 29, 47, 76]);
+        let p: Vec<u64> = pell().take(10).collect();
+        assert_eq!(p, vec![0, 1, 2, 5, 12, 29, 70, 169, 408, 985]);
+        let t: Vec<u64> = tribonacci().take(12).collect();
+        assert_eq!(t, vec![0, 0, 1, 1, 2, 4, 7, 13, 24, 44, 81, 149]);
+        // negative coefficients, a(n) = 2 a(n-1) - a(n-2) counts up
+        let a: Vec<i64> = LinearRecurrence::new(vec![2, -1], vec![5, 6])
+            .take(5)
+            .collect();
+        assert_eq!(a, vec![5, 6, 7, 8, 9]);
+        // F(93) is the last u64, and the iterator does not compute F(94)
+        assert_eq!(fibonacci::<u64>().nth(93), Some(12200160415121876738));
+        let big = fibonacci::<BigUint>().nth(1000).unwrap();
+        assert_eq!(big, fib::fibonacci_big(1000));
+    }
+    #[test]
+    pub fn term_test() {
+        for &(coefficients, seeds) in [
+            (&[1i64, 1][..], &[0i64, 1][..]),
+            (&[2, 1], &[0, 1]),
+            (&[1, 1, 1], &[0, 0, 1]),
+            (&[3, -2, 0, 1], &[1, -4, 2, 7]),
+            (&[7], &[3]),
+        ]
+        .iter()
+        {
+            let r = LinearRecurrence::new(coefficients.to_vec(), seeds.to_vec());
+            for (n, a) in r.clone().take(20).enumerate() {
+                assert_eq!(r.term(n as u64), a);
+            }
+        }
+        let f = fibonacci::<BigUint>();
+        assert_eq!(f.term(4782), fib::fibonacci_big(4782));
+        assert_eq!(
+            lucas::<u128>().term(100),
+            fib::fib_fast(99) + fib::fib_fast(101)
+        );
+    }
+    #[test]
+    pub fn berlekamp_massey_test() {
+        let to_i64 = |c: Vec<Ratio>| -> Vec<i64> { c.iter().map(|r| r.numer()).collect() };
+        let t: Vec<i64> = tribonacci().take(20).collect();
+        assert_eq!(to_i64(berlekamp_massey(&t)), vec![1, 1, 1]);
+        let a: Vec<i64> = LinearRecurrence::new(vec![3, -2, 0, 1], vec![1, -4, 2, 7])
+            .take(12)
+            .collect();
+        assert_eq!(to_i64(berlekamp_massey(&a)), vec![3, -2, 0, 1]);
+        // the squares satisfy a(n) = 3 a(n-1) - 3 a(n-2) + a(n-3)
+        let squares: Vec<i64> = (0..10).map(|n| n * n).collect();
+        let r = from_terms(&squares).unwrap();
+        assert_eq!(r.term(1000), 1_000_000);
+        assert_eq!(berlekamp_massey(&[0, 0, 0]), vec![]);
+        // 1, 2, 3, 5 fits a(n) = a(n-1) + a(n-2), but 4, 2, 1 halves each time
+        assert_eq!(to_i64(berlekamp_massey(&[1, 2, 3, 5])), vec![1, 1]);
+        assert_eq!(berlekamp_massey(&[4, 2, 1]), vec![Ratio::new(1, 2)]);
+        assert!(from_terms(&[4, 2, 1]).is_none());
+    }
+}
@@ -0,0 +1,207 @@
+//! Smallest Prime Factor Sieve
+//!
+//! A [linear sieve] that records the smallest prime factor (spf) of every
+//! number up to N.  It is built in O(N), and any n <= N can then be factored
+//! in O(log n) by repeatedly dividing by the smallest prime factor.
+//!
+//! Only the odd numbers are stored (the smallest factor of an even number is 2).
+//! With the compact (u32) storage, a sieve to 10^8 needs about 200MB.
+//!
+//! [linear sieve]: https://cp-algorithms.com/algebra/prime-sieve-linear.html
+
+/// The type used to store a smallest prime factor
+///
+/// u32 is compact, but limits the sieve to numbers below 2^32
+pub trait SpfEntry: Copy + Default + PartialEq {
+    fn from_usize(n: usize) -> Self;
+    fn to_usize(self) -> usize;
+}
+
+impl SpfEntry for u32 {
+    fn from_usize(n: usize) -> u32 {
+        n as u32
+    }
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+}
+
+impl SpfEntry for usize {
+    fn from_usize(n: usize) -> usize {
+        n
+    }
+    fn to_usize(self) -> usize {
+        self
+    }
+}
+
+/// A table of the smallest prime factor of every number up to a limit
+pub struct SpfSieve<T: SpfEntry = usize> {
+    limit: usize,
+    // index i is the odd number 2i+1; a zero is used for 1
+    spf: Vec<T>,
+}
+
+impl SpfSieve<usize> {
+    /// Returns a smallest prime factor sieve of the numbers up to and including n
+    ///
+    /// # Examples
+    /// ```
+    /// let sieve = SpfSieve::new(100);
+    /// assert_eq!(sieve.factor(90), vec![(2, 1), (3, 2), (5, 1)]);
+    /// ```
+    pub fn new(n: usize) -> SpfSieve<usize> {
+        SpfSieve::build(n)
+    }
+}
+
+impl SpfSieve<u32> {
+    /// Returns a sieve that stores the factors as u32, using half the memory
+    ///
+    /// # Panics
+    ///
+    /// will panic if n does not fit in a u32
+    #[allow(dead_code)]
+    pub fn compact(n: usize) -> SpfSieve<u32> {
+        assert!(
+            n <= u32::MAX as usize,
+            "{} is too large for a compact sieve",
+            n
+        );
+        SpfSieve::build(n)
+    }
+}
+
+impl<T: SpfEntry> SpfSieve<T> {
+    fn build(n: usize) -> SpfSieve<T> {
+        let zero = T::default();
+        let mut spf = vec![zero; n.div_ceil(2)];
+        let mut primes: Vec<T> = Vec::new();
+        // Every odd composite m is marked exactly once, as p * i where p = spf(m)
+        for i in (3..=n).step_by(2) {
+            let mut spf_i = spf[i / 2];
+            if spf_i == zero {
+                spf_i = T::from_usize(i);
+                spf[i / 2] = spf_i;
+                primes.push(spf_i);
+            }
+            let spf_i = spf_i.to_usize();
+            for &p in primes.iter() {
+                let p_usize = p.to_usize();
+                if p_usize > spf_i || i * p_usize > n {
+                    break;
+                }
+                spf[i * p_usize / 2] = p;
+            }
+        }
+        SpfSieve { limit: n, spf }
+    }
+
+    /// The largest number in the sieve
+    #[allow(dead_code)]
+    pub fn upper_bound(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns the smallest prime factor of n, or 1 if n < 2
+    ///
+    /// # Panics
+    ///
+    /// will panic if n is greater than the upper bound of the sieve
+    pub fn smallest_factor(&self, n: usize) -> usize {
+        assert!(n <= self.limit, "{} is beyond the sieve limit", n);
+        if n < 2 {
+            1
+        } else if n.is_multiple_of(2) {
+            2
+        } else {
+            self.spf[n / 2].to_usize()
+        }
+    }
+
+    /// Returns true if n is prime
+    pub fn is_prime(&self, n: usize) -> bool {
+        n > 1 && self.smallest_factor(n) == n
+    }
+
+    /// Returns an iterator over the primes in the sieve
+    pub fn primes(&self) -> impl Iterator<Item = usize> + '_ {
+        let two = if self.limit >= 2 { Some(2) } else { None };
+        two.into_iter().chain(
+            (3..=self.limit)
+                .step_by(2)
+                .filter(move |&n| self.spf[n / 2].to_usize() == n),
+        )
+    }
+
+    /// Returns an iterator over the (prime, exponent) factors of n
+    ///
+    /// The primes are in ascending order.  This does not allocate, so it is
+    /// preferred over factor() in hot loops.
+    pub fn factors(&self, n: usize) -> Factors<'_, T> {
+        assert!(n <= self.limit, "{} is beyond the sieve limit", n);
+        Factors { sieve: self, n }
+    }
+
+    /// Returns the prime factorization of n as a vector of (prime, exponent)
+    ///
+    /// This is the same shape as primal::Sieve::factor(), but without the Result.
+    /// The factorization of 1 is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// let sieve = SpfSieve::new(100);
+    /// assert_eq!(sieve.factor(90), vec![(2, 1), (3, 2), (5, 1)]);
+    /// ```
+    pub fn factor(&self, n: usize) -> Vec<(usize, usize)> {
+        self.factors(n).collect()
+    }
+}
+
+/// Iterator over the (prime, exponent) factors of a number
+pub struct Factors<'a, T: SpfEntry> {
+    sieve: &'a SpfSieve<T>,
+    n: usize,
+}
+
+impl<'a, T: SpfEntry> Iterator for Factors<'a, T> {
+    type Item = (usize, usize);
+    fn next(&mut self) -> Option<(usize, usize)> {
+        if self.n < 2 {
+            return None;
+        }
+        let p = self.sieve.smallest_factor(self.n);
+        let mut e = 0;
+        while self.n.is_multiple_of(p) {
+            self.n /= p;
+            e += 1;
+        }
+        Some((p, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn spf_factor_test() {
+        let sieve = SpfSieve::new(1000);
+        assert_eq!(sieve.factor(1), vec![]);
+        assert_eq!(sieve.factor(2), vec![(2, 1)]);
+        assert_eq!(sieve.factor(997), vec![(997, 1)]);
+        assert_eq!(sieve.factor(1000), vec![(2, 3), (5, 3)]);
+        assert_eq!(sieve.factor(999), vec![(3, 3), (37, 1)]);
+    }
+    #[test]
+    pub fn spf_matches_primal_test() {
+        let n = 100_000;
+        let sieve = SpfSieve::compact(n);
+        let primal = primal::Sieve::new(n);
+        for i in 2..=n {
+            assert_eq!(sieve.factor(i), primal.factor(i).unwrap());
+            assert_eq!(sieve.is_prime(i), primal.is_prime(i));
+        }
+        assert!(sieve.primes().eq(primal.primes_from(0)));
+    }
+}
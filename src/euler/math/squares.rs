@@ -0,0 +1,182 @@
+//! Sums of Two Squares
+//!
+//! Which n can be written as n = a^2 + b^2, and in how many ways, is decided by
+//! the prime factorization of n ([Fermat's theorem on sums of two squares]):
+//!
+//! * 2 = 1^2 + 1^2
+//! * a prime p = 1 (mod 4) is a sum of two squares in exactly one way
+//! * a prime q = 3 (mod 4) is not, and q must divide n an even number of times
+//!
+//! Counting the ordered, signed pairs (a, b), [r2(n)] is 4 times the product of
+//! (e + 1) over the primes p^e = 1 (mod 4), or 0 if some q = 3 (mod 4) has an
+//! odd exponent.
+//!
+//! The representations themselves come from factoring in the Gaussian integers
+//! a + bi (see gaussian.rs), where a^2 + b^2 = (a + bi)(a - bi).  Each
+//! p = 1 (mod 4) splits as (x + yi)(x - yi), found with Cornacchia's algorithm
+//! (gaussian::prime_two_squares), and every representation of n is a product
+//! that takes k factors of x + yi and e - k of x - yi for each p^e.
+//!
+//! [Fermat's theorem on sums of two squares]: https://en.wikipedia.org/wiki/Fermat%27s_theorem_on_sums_of_two_squares
+//! [r2(n)]: https://en.wikipedia.org/wiki/Sum_of_squares_function
+
+use super::{primality, GaussianInt};
+
+/// Returns r2(n) from the prime factorization of n
+///
+/// The factorization does not have to fit in a u64, so this counts the
+/// representations of numbers too big to factor directly, such as 2N^2.
+///
+/// # Examples
+/// ```
+/// // 25 = 5^2 = (±5)^2 + 0^2 = (±3)^2 + (±4)^2 = (±4)^2 + (±3)^2
+/// assert_eq!(r2_from_factors(&[(5, 2)]), 12);
+/// ```
+pub fn r2_from_factors(factors: &[(u64, usize)]) -> u64 {
+    let mut count = 4;
+    for &(p, e) in factors {
+        match p % 4 {
+            1 => count *= e as u64 + 1,
+            3 if e % 2 == 1 => return 0,
+            _ => (),
+        }
+    }
+    count
+}
+
+/// Returns r2(n), the number of pairs of integers (a, b) with a^2 + b^2 = n
+///
+/// The pairs are ordered and signed, so they are the lattice points on the
+/// circle of radius √n around the origin.
+///
+/// # Examples
+/// ```
+/// // (±1, 0), (0, ±1)
+/// assert_eq!(r2(1), 4);
+/// assert_eq!(r2(3), 0);
+/// assert_eq!(r2(65), 16);
+/// ```
+#[allow(dead_code)]
+pub fn r2(n: u64) -> u64 {
+    if n == 0 {
+        return 1;
+    }
+    r2_from_factors(&primality::factor(n))
+}
+
+/// Returns true if n = a^2 + b^2 for some integers a and b
+///
+/// # Examples
+/// ```
+/// assert!(is_sum_of_two_squares(450));
+/// assert!(!is_sum_of_two_squares(21));
+/// ```
+#[allow(dead_code)]
+pub fn is_sum_of_two_squares(n: u64) -> bool {
+    n == 0 || r2(n) > 0
+}
+
+/// Returns every (a, b) with a >= b >= 0 and a^2 + b^2 = n, in ascending order
+///
+/// These are the Gaussian integers a + bi with norm n (see
+/// GaussianInt::with_norm), with a and b swapped where b > a, since b + ai is
+/// an associate of a - bi.
+///
+/// # Examples
+/// ```
+/// assert_eq!(two_squares(65), vec![(7, 4), (8, 1)]);
+/// assert_eq!(two_squares(50), vec![(5, 5), (7, 1)]);
+/// assert_eq!(two_squares(21), vec![]);
+/// ```
+#[allow(dead_code)]
+pub fn two_squares(n: u64) -> Vec<(u64, u64)> {
+    let mut pairs: Vec<(u64, u64)> = GaussianInt::with_norm(n)
+        .into_iter()
+        .map(|z| (z.re.max(z.im) as u64, z.re.min(z.im) as u64))
+        .collect();
+    pairs.sort_unstable();
+    pairs.dedup();
+    pairs
+}
+
+/// Returns the number of lattice points on the circle through (0,0), (N,0), (0,N) and (N,N)
+///
+/// The circle has center (N/2, N/2) and radius^2 = N^2/2, so (x, y) is on it
+/// when (2x - N)^2 + (2y - N)^2 = 2N^2.  Every solution (u, v) of
+/// u^2 + v^2 = 2N^2 has u and v of the same parity as N, so this is r2(2N^2).
+/// That is counted from the factorization of N, with the exponents doubled,
+/// so it works for every N even though 2N^2 overflows.
+///
+/// # Examples
+/// ```
+/// // Euler problem 233
+/// assert_eq!(circle_lattice_points(10000), 36);
+/// ```
+#[allow(dead_code)]
+pub fn circle_lattice_points(n: u64) -> u64 {
+    if n == 0 {
+        // the circle is the single point (0, 0)
+        return 1;
+    }
+    // the extra factor of 2 in 2N^2 does not change r2, so it is left out
+    let factors: Vec<(u64, usize)> = primality::factor(n)
+        .into_iter()
+        .map(|(p, e)| (p, 2 * e))
+        .collect();
+    r2_from_factors(&factors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn two_squares_test() {
+        let mut brute: Vec<Vec<(u64, u64)>> = vec![Vec::new(); 5001];
+        for a in 0..=70u64 {
+            for b in 0..=a {
+                if a * a + b * b <= 5000 {
+                    brute[(a * a + b * b) as usize].push((a, b));
+                }
+            }
+        }
+        for n in 1..=5000u64 {
+            let mut expected = brute[n as usize].clone();
+            expected.sort_unstable();
+            assert_eq!(two_squares(n), expected, "n = {}", n);
+            // each (a, b) gives 8 signed, ordered pairs, unless a = b or b = 0
+            let count: u64 = expected
+                .iter()
+                .map(|&(a, b)| if a == b || b == 0 { 4 } else { 8 })
+                .sum();
+            assert_eq!(r2(n), count, "n = {}", n);
+            assert_eq!(is_sum_of_two_squares(n), count > 0);
+        }
+        assert_eq!(r2(0), 1);
+        // 5 * 13 * 17 * 29 * 37 * 41 * 53 * 61 * 73 * 89 * 97 has 2^10 representations
+        let n = 5 * 13 * 17 * 29 * 37 * 41 * 53 * 61 * 73 * 89 * 97;
+        let reps = two_squares(n);
+        assert_eq!(reps.len(), 1024);
+        assert!(reps.iter().all(|&(a, b)| a * a + b * b == n));
+    }
+    #[test]
+    pub fn circle_lattice_points_test() {
+        for n in 0..=100i64 {
+            let mut count = 0;
+            for x in -n..=2 * n {
+                for y in -n..=2 * n {
+                    if (2 * x - n).pow(2) + (2 * y - n).pow(2) == 2 * n * n {
+                        count += 1;
+                    }
+                }
+            }
+            assert_eq!(circle_lattice_points(n as u64), count, "n = {}", n);
+        }
+        // 10^k = 2^k 5^k, so there are 4(2k + 1) points
+        let mut n = 1;
+        for k in 0..=19 {
+            assert_eq!(circle_lattice_points(n), 4 * (2 * k + 1));
+            n = n.saturating_mul(10);
+        }
+    }
+}
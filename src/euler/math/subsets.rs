@@ -0,0 +1,426 @@
+//! Subsets, Combinations and Permutations
+//!
+//! Lazy iterators over the subsets, k-combinations and permutations of a
+//! borrowed slice.
+//!
+//! A subset of a slice with n < 64 items is a bitmask, where bit i is set if
+//! items[i] is in the subset.  The subset iterators only step a u64, and yield a
+//! Subset, which is itself an iterator over references to the chosen items, so
+//! nothing is allocated or cloned.
+//!
+//! * subsets() counts the mask from 0 to 2^n - 1
+//! * gray_subsets() visits the masks in [Gray code] order, so each subset
+//!   differs from the previous one by adding or removing a single item.  This
+//!   lets a running product or sum be updated in O(1), rather than recomputed.
+//! * combinations() visits only the masks with k bits set, in increasing order,
+//!   using [Gosper's hack].
+//!
+//! Permutations cannot be represented by a mask.  next_permutation() steps a
+//! mutable slice to its next lexicographic order in place, and is the
+//! allocation-free way to visit them.  permutations() and multiset_permutations()
+//! wrap it in iterators that yield a Vec of references.
+//!
+//! [Gray code]: https://en.wikipedia.org/wiki/Gray_code
+//! [Gosper's hack]: https://en.wikipedia.org/wiki/Combinatorial_number_system#Applications
+
+/// A subset of a slice, as an iterator over references to its items
+///
+/// Items are produced in the order they appear in the slice.
+#[derive(Clone, Copy, Debug)]
+pub struct Subset<'a, T> {
+    items: &'a [T],
+    mask: u64,
+}
+
+#[allow(dead_code)]
+impl<'a, T> Subset<'a, T> {
+    /// Returns the bitmask of the subset, bit i is set if items[i] is included
+    pub fn mask(&self) -> u64 {
+        self.mask
+    }
+
+    /// Returns true if items[i] is in the subset
+    pub fn contains(&self, i: usize) -> bool {
+        i < 64 && self.mask & (1 << i) != 0
+    }
+}
+
+impl<'a, T> Iterator for Subset<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        if self.mask == 0 {
+            return None;
+        }
+        let i = self.mask.trailing_zeros() as usize;
+        self.mask &= self.mask - 1;
+        Some(&self.items[i])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.mask.count_ones() as usize;
+        (n, Some(n))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Subset<'a, T> {}
+
+/// Returns the mask with the lowest n bits set
+///
+/// # Panics
+///
+/// will panic if n is 64 or more, since there are too many subsets to visit
+fn full_mask(n: usize) -> u64 {
+    assert!(n < 64, "too many items for a u64 mask");
+    (1 << n) - 1
+}
+
+/// An iterator over all the subsets of a slice, in bitmask order
+pub struct Subsets<'a, T> {
+    items: &'a [T],
+    next: u64,
+    last: u64,
+    done: bool,
+}
+
+impl<'a, T> Iterator for Subsets<'a, T> {
+    type Item = Subset<'a, T>;
+    fn next(&mut self) -> Option<Subset<'a, T>> {
+        if self.done {
+            return None;
+        }
+        let mask = self.next;
+        self.done = mask == self.last;
+        self.next += 1;
+        Some(Subset {
+            items: self.items,
+            mask,
+        })
+    }
+}
+
+/// Returns an iterator over the 2^n subsets of items, starting with the empty set
+///
+/// The subsets are in the order of their bitmask, so the first item is
+/// included in every other subset.  Given [a, b, c] the subsets are
+/// [], [a], [b], [a, b], [c], [a, c], [b, c], [a, b, c].
+///
+/// This can be used to generate the divisors of a number, as every divisor of a
+/// squarefree number is the product of a subset of its prime factors.
+///
+/// # Examples
+/// ```
+/// let sums: Vec<i32> = subsets(&[1, 2, 4]).map(|s| s.sum()).collect();
+/// assert_eq!(sums, vec![0, 1, 2, 3, 4, 5, 6, 7]);
+/// ```
+///
+/// # Panics
+///
+/// will panic if items has 64 or more items
+#[allow(dead_code)]
+pub fn subsets<T>(items: &[T]) -> Subsets<'_, T> {
+    Subsets {
+        items,
+        next: 0,
+        last: full_mask(items.len()),
+        done: false,
+    }
+}
+
+/// An iterator over all the subsets of a slice, in Gray code order
+pub struct GraySubsets<'a, T> {
+    items: &'a [T],
+    i: u64,
+    last: u64,
+    done: bool,
+}
+
+impl<'a, T> Iterator for GraySubsets<'a, T> {
+    type Item = Subset<'a, T>;
+    fn next(&mut self) -> Option<Subset<'a, T>> {
+        if self.done {
+            return None;
+        }
+        let mask = self.i ^ (self.i >> 1);
+        self.done = self.i == self.last;
+        self.i += 1;
+        Some(Subset {
+            items: self.items,
+            mask,
+        })
+    }
+}
+
+/// Returns an iterator over the 2^n subsets of items, in Gray code order
+///
+/// Each subset differs from the previous one by exactly one item, which is
+/// items[i] where i is the index of the only bit set in the xor of the masks.
+/// Given [a, b, c] the subsets are
+/// [], [a], [a, b], [b], [b, c], [a, b, c], [a, c], [c].
+///
+/// # Examples
+/// ```
+/// let masks: Vec<u64> = gray_subsets(&[1, 2, 3]).map(|s| s.mask()).collect();
+/// assert_eq!(masks, vec![0, 1, 3, 2, 6, 7, 5, 4]);
+/// ```
+///
+/// # Panics
+///
+/// will panic if items has 64 or more items
+#[allow(dead_code)]
+pub fn gray_subsets<T>(items: &[T]) -> GraySubsets<'_, T> {
+    GraySubsets {
+        items,
+        i: 0,
+        last: full_mask(items.len()),
+        done: false,
+    }
+}
+
+/// An iterator over the subsets of a slice with exactly k items
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+    next: Option<u64>,
+    last: u64,
+}
+
+impl<'a, T> Iterator for Combinations<'a, T> {
+    type Item = Subset<'a, T>;
+    fn next(&mut self) -> Option<Subset<'a, T>> {
+        let mask = self.next?;
+        self.next = if mask >= self.last {
+            None
+        } else {
+            // Gosper's hack: move the lowest block of ones up by one place,
+            // and the rest of the block back down to the bottom
+            let lowest = mask & mask.wrapping_neg();
+            let ripple = mask + lowest;
+            Some((((ripple ^ mask) >> 2) / lowest) | ripple)
+        };
+        Some(Subset {
+            items: self.items,
+            mask,
+        })
+    }
+}
+
+/// Returns an iterator over the C(n, k) subsets of items with k items
+///
+/// The combinations are in increasing order of their bitmask, and the items in
+/// each combination are in the order they appear in the slice.  If k > n there
+/// are no combinations, and if k = 0 there is one, the empty set.
+///
+/// # Examples
+/// ```
+/// let c: Vec<Vec<&char>> = combinations(&['a', 'b', 'c', 'd'], 2)
+///     .map(|s| s.collect())
+///     .collect();
+/// // ab, ac, bc, ad, bd, cd
+/// assert_eq!(c[2], vec![&'b', &'c']);
+/// ```
+///
+/// # Panics
+///
+/// will panic if items has 64 or more items
+#[allow(dead_code)]
+pub fn combinations<T>(items: &[T], k: usize) -> Combinations<'_, T> {
+    let n = items.len();
+    let all = full_mask(n);
+    Combinations {
+        items,
+        next: if k <= n { Some(full_mask(k)) } else { None },
+        // the largest mask has its k bits at the top
+        last: if k <= n { all ^ full_mask(n - k) } else { 0 },
+    }
+}
+
+/// Rearranges v into the next lexicographic permutation, and returns true
+///
+/// If v is the last permutation (in descending order), it is reversed to the
+/// first (ascending order) and false is returned.  Equal items are not swapped,
+/// so starting from a sorted slice this visits each distinct arrangement of a
+/// multiset once.
+///
+/// # Examples
+/// ```
+/// let mut v = [1, 2, 3];
+/// assert!(next_permutation(&mut v));
+/// assert_eq!(v, [1, 3, 2]);
+/// let mut v = [3, 2, 1];
+/// assert!(!next_permutation(&mut v));
+/// assert_eq!(v, [1, 2, 3]);
+/// ```
+pub fn next_permutation<T: Ord>(v: &mut [T]) -> bool {
+    // find the longest descending suffix, v[i..]
+    let mut i = v.len();
+    while i > 1 && v[i - 2] >= v[i - 1] {
+        i -= 1;
+    }
+    if i <= 1 {
+        v.reverse();
+        return false;
+    }
+    // swap the pivot with the smallest larger item in the suffix
+    let pivot = i - 2;
+    let mut j = v.len() - 1;
+    while v[j] <= v[pivot] {
+        j -= 1;
+    }
+    v.swap(pivot, j);
+    v[i - 1..].reverse();
+    true
+}
+
+/// An iterator over the permutations of a slice
+///
+/// It keeps the indices of the items and steps them with next_permutation().
+pub struct Permutations<'a, T> {
+    items: &'a [T],
+    order: Vec<usize>,
+    done: bool,
+}
+
+impl<'a, T> Iterator for Permutations<'a, T> {
+    type Item = Vec<&'a T>;
+    fn next(&mut self) -> Option<Vec<&'a T>> {
+        if self.done {
+            return None;
+        }
+        let permutation = self.order.iter().map(|&i| &self.items[i]).collect();
+        self.done = !next_permutation(&mut self.order);
+        Some(permutation)
+    }
+}
+
+/// Returns an iterator over the n! permutations of items
+///
+/// The items are treated as distinct, even if they are equal, and the
+/// permutations are in lexicographic order of their positions in the slice.
+///
+/// # Examples
+/// ```
+/// let p: Vec<Vec<&i32>> = permutations(&[1, 2, 3]).collect();
+/// assert_eq!(p.len(), 6);
+/// assert_eq!(p[1], vec![&1, &3, &2]);
+/// ```
+#[allow(dead_code)]
+pub fn permutations<T>(items: &[T]) -> Permutations<'_, T> {
+    Permutations {
+        items,
+        order: (0..items.len()).collect(),
+        done: false,
+    }
+}
+
+/// An iterator over the distinct permutations of a multiset
+pub struct MultisetPermutations<'a, T> {
+    order: Vec<&'a T>,
+    done: bool,
+}
+
+impl<'a, T: Ord> Iterator for MultisetPermutations<'a, T> {
+    type Item = Vec<&'a T>;
+    fn next(&mut self) -> Option<Vec<&'a T>> {
+        if self.done {
+            return None;
+        }
+        let permutation = self.order.clone();
+        self.done = !next_permutation(&mut self.order);
+        Some(permutation)
+    }
+}
+
+/// Returns an iterator over the distinct permutations of items, in lexicographic order
+///
+/// Equal items are interchangeable, so there are n! / (m1! m2! ...) permutations,
+/// where m1, m2, ... are the multiplicities of the distinct items.
+///
+/// # Examples
+/// ```
+/// let p: Vec<Vec<&i32>> = multiset_permutations(&[2, 1, 1]).collect();
+/// assert_eq!(p, vec![vec![&1, &1, &2], vec![&1, &2, &1], vec![&2, &1, &1]]);
+/// ```
+#[allow(dead_code)]
+pub fn multiset_permutations<T: Ord>(items: &[T]) -> MultisetPermutations<'_, T> {
+    let mut order: Vec<&T> = items.iter().collect();
+    order.sort();
+    MultisetPermutations { order, done: false }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn subsets_test() {
+        let items = ['a', 'b', 'c'];
+        let s: Vec<String> = subsets(&items).map(|s| s.collect()).collect();
+        assert_eq!(s, vec!["", "a", "b", "ab", "c", "ac", "bc", "abc"]);
+        assert_eq!(subsets::<u8>(&[]).count(), 1);
+        // every divisor of 30030 is the product of a subset of its prime factors
+        let primes = [2, 3, 5, 7, 11, 13];
+        let mut divisors: Vec<u64> = subsets(&primes).map(|s| s.product()).collect();
+        divisors.sort_unstable();
+        let expected: Vec<u64> = (1..=30030).filter(|d| 30030 % d == 0).collect();
+        assert_eq!(divisors, expected);
+    }
+    #[test]
+    pub fn gray_subsets_test() {
+        let items: Vec<u32> = (0..10).collect();
+        let masks: Vec<u64> = gray_subsets(&items).map(|s| s.mask()).collect();
+        assert_eq!(masks.len(), 1024);
+        for w in masks.windows(2) {
+            assert_eq!((w[0] ^ w[1]).count_ones(), 1);
+        }
+        let mut sorted = masks.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..1024).collect::<Vec<u64>>());
+        let s: Vec<String> = gray_subsets(&['a', 'b', 'c'])
+            .map(|s| s.collect())
+            .collect();
+        assert_eq!(s, vec!["", "a", "ab", "b", "bc", "abc", "ac", "c"]);
+    }
+    #[test]
+    pub fn combinations_test() {
+        let items: Vec<u32> = (0..12).collect();
+        for k in 0..=13 {
+            let masks: Vec<u64> = combinations(&items, k).map(|s| s.mask()).collect();
+            let expected: Vec<u64> = (0..1 << 12)
+                .filter(|m: &u64| m.count_ones() as usize == k)
+                .collect();
+            assert_eq!(masks, expected);
+        }
+        // a full 63 bit mask does not overflow
+        let items = [0u8; 63];
+        assert_eq!(combinations(&items, 62).count(), 63);
+        assert_eq!(combinations(&items, 63).count(), 1);
+        let c: Vec<Vec<&char>> = combinations(&['a', 'b', 'c', 'd'], 3)
+            .map(|s| s.collect())
+            .collect();
+        assert_eq!(c[3], vec![&'b', &'c', &'d']);
+    }
+    #[test]
+    pub fn permutations_test() {
+        let p: Vec<String> = permutations(&['a', 'b', 'c'])
+            .map(|p| p.into_iter().collect())
+            .collect();
+        assert_eq!(p, vec!["abc", "acb", "bac", "bca", "cab", "cba"]);
+        assert_eq!(permutations(&[1, 1, 1, 1, 1]).count(), 120);
+        assert_eq!(permutations::<u8>(&[]).count(), 1);
+        // Euler problem 24, the millionth permutation of the digits 0 to 9
+        let mut digits = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        for _ in 1..1_000_000 {
+            next_permutation(&mut digits);
+        }
+        assert_eq!(digits, [2, 7, 8, 3, 9, 1, 5, 4, 6, 0]);
+    }
+    #[test]
+    pub fn multiset_permutations_test() {
+        let p: Vec<String> = multiset_permutations(&['b', 'a', 'b', 'a'])
+            .map(|p| p.into_iter().collect())
+            .collect();
+        assert_eq!(p, vec!["aabb", "abab", "abba", "baab", "baba", "bbaa"]);
+        // 11! / (4! 4! 2! 1!) arrangements of MISSISSIPPI
+        let letters: Vec<char> = "MISSISSIPPI".chars().collect();
+        assert_eq!(multiset_permutations(&letters).count(), 34650);
+    }
+}
@@ -0,0 +1,249 @@
+//! Summatory Functions
+//!
+//! Sums of arithmetic functions up to x, without a sieve up to x.
+//!
+//! * prime_count(x), prime_sum(x): π(x) and the sum of the primes up to x,
+//!   with the [Lucy_Hedgehog] algorithm in O(x^(3/4) / log x) time and O(√x) memory.
+//! * mertens(x), totient_sum(x): the sums of μ(n) and φ(n) up to x, with the
+//!   Dirichlet hyperbola method in O(x^(2/3)) time.
+//!
+//! Both only ever need the values of the sums at x/k, for each k.  There are
+//! only about 2√x distinct values, since x/k takes every value up to √x, and
+//! for k up to √x the values above √x are all different.
+//!
+//! Lucy_Hedgehog starts with S(v) = the sum of f(n) for 2 <= n <= v, and for each
+//! prime p up to √x removes the numbers whose smallest prime factor is p, from
+//! every S(v) with v >= p^2.  What is left is the sum over the primes.
+//!
+//! For mertens and totient_sum, summing f * 1 (the Dirichlet convolution) over
+//! the hyperbola ab <= x gives the recurrence
+//!
+//!     F(x) = G(x) - Σ F(x/d)   for d = 2 to x
+//!
+//! where G(x) is 1 for μ (since μ * 1 is 1 at n = 1 and 0 elsewhere) and
+//! x(x+1)/2 for φ (since φ * 1 = n).  There are only O(√v) distinct x/d, so
+//! each F(v) takes O(√v) steps.  The values up to x^(2/3) are sieved instead.
+//!
+//! [Lucy_Hedgehog]: https://projecteuler.net/thread=10;page=5#111677
+
+use super::divisor_sieve;
+use num_integer::Roots;
+
+/// The largest sieve used by mertens and totient_sum, to bound their memory
+const MAX_SIEVE: usize = 1 << 24;
+
+/// Returns Σ g(p) for the primes p <= x
+///
+/// initial(v) is Σ g(n) for 2 <= n <= v, and g must be completely
+/// multiplicative, so that g(p m) = g(p) g(m).
+fn lucy_hedgehog<I, G>(x: u64, initial: I, g: G) -> u128
+where
+    I: Fn(u64) -> u128,
+    G: Fn(u64) -> u128,
+{
+    if x < 2 {
+        return 0;
+    }
+    let r = x.sqrt();
+    // small[v] = S(v) for v <= r, and large[i] = S(x/i) for i <= r
+    let mut small: Vec<u128> = (0..=r).map(&initial).collect();
+    let mut large: Vec<u128> = std::iter::once(0)
+        .chain((1..=r).map(|i| initial(x / i)))
+        .collect();
+    for p in 2..=r {
+        if small[p as usize] == small[p as usize - 1] {
+            // p is not prime, it was removed by a smaller prime
+            continue;
+        }
+        let below_p = small[p as usize - 1];
+        let gp = g(p);
+        let p2 = p * p;
+        for i in 1..=r.min(x / p2) {
+            let ip = i * p;
+            let s = if ip <= r {
+                large[ip as usize]
+            } else {
+                small[(x / ip) as usize]
+            };
+            large[i as usize] -= gp * (s - below_p);
+        }
+        for v in (p2..=r).rev() {
+            small[v as usize] -= gp * (small[(v / p) as usize] - below_p);
+        }
+    }
+    large[1]
+}
+
+/// Returns π(x), the number of primes p <= x
+///
+/// # Examples
+/// ```
+/// assert_eq!(prime_count(100), 25);
+/// assert_eq!(prime_count(1_000_000_000_000), 37_607_912_018);
+/// ```
+pub fn prime_count(x: u64) -> u64 {
+    lucy_hedgehog(x, |v| v.saturating_sub(1) as u128, |_| 1) as u64
+}
+
+/// Returns the sum of the primes p <= x
+///
+/// # Examples
+/// ```
+/// assert_eq!(prime_sum(10), 17);
+/// assert_eq!(prime_sum(2_000_000), 142_913_828_922);  // Euler problem 10
+/// ```
+#[allow(dead_code)]
+pub fn prime_sum(x: u64) -> u128 {
+    lucy_hedgehog(
+        x,
+        |v| (v as u128 * (v as u128 + 1) / 2).saturating_sub(1),
+        |p| p as u128,
+    )
+}
+
+/// Returns F(x) where F(v) = total(v) - Σ F(v/d) for d = 2 to v
+///
+/// small[v] must be F(v) for every v up to some limit (at least √x).  The
+/// values F(x/k) above the limit are computed for k from large to small, so
+/// every F(x/(kd)) is known by the time F(x/k) needs it.
+fn hyperbola<T>(x: u64, small: &[i64], total: T) -> i128
+where
+    T: Fn(u64) -> i128,
+{
+    let limit = small.len() as u64 - 1;
+    if x <= limit {
+        return small[x as usize] as i128;
+    }
+    // big[k] = F(x/k) for each k with x/k > limit
+    let k_max = x / (limit + 1);
+    let mut big = vec![0i128; k_max as usize + 1];
+    for k in (1..=k_max).rev() {
+        let v = x / k;
+        let mut f = total(v);
+        let mut d = 2;
+        while d <= v {
+            let q = v / d;
+            let d_end = v / q;
+            let fq = if q <= limit {
+                small[q as usize] as i128
+            } else {
+                // q = x/(kd) > limit, so kd <= k_max, and kd > k was done already
+                big[(k * d) as usize]
+            };
+            f -= (d_end - d + 1) as i128 * fq;
+            d = d_end + 1;
+        }
+        big[k as usize] = f;
+    }
+    big[1]
+}
+
+/// Returns the sieve limit for the hyperbola method, about x^(2/3)
+fn sieve_limit(x: u64) -> usize {
+    let c = x.cbrt() + 1;
+    ((c * c) as usize).clamp(x.sqrt() as usize + 1, MAX_SIEVE.max(x.sqrt() as usize + 1))
+}
+
+/// Returns the Mertens function M(x), the sum of μ(n) for n <= x
+///
+/// μ(n) is sieved up to about x^(2/3) (at most 2^24), and the rest comes from
+/// M(x) = 1 - Σ M(x/d) for d = 2 to x.
+///
+/// # Examples
+/// ```
+/// assert_eq!(mertens(10), -1);
+/// assert_eq!(mertens(1_000_000_000), -222);
+/// ```
+#[allow(dead_code)]
+pub fn mertens(x: u64) -> i64 {
+    let limit = sieve_limit(x);
+    let mut m: Vec<i64> = divisor_sieve::mobius(limit)
+        .into_iter()
+        .map(|mu| mu as i64)
+        .collect();
+    m[0] = 0;
+    for i in 1..m.len() {
+        m[i] += m[i - 1];
+    }
+    hyperbola(x, &m, |_| 1) as i64
+}
+
+/// Returns Φ(x), the sum of the totients φ(n) for n <= x
+///
+/// φ(n) is sieved up to about x^(2/3) (at most 2^24), and the rest comes from
+/// Φ(x) = x(x+1)/2 - Σ Φ(x/d) for d = 2 to x.  Φ(x) counts the fractions
+/// a/b in (0, 1] with b <= x in lowest terms.
+///
+/// # Examples
+/// ```
+/// assert_eq!(totient_sum(10), 32);
+/// assert_eq!(totient_sum(1_000_000), 303_963_552_392);
+/// ```
+#[allow(dead_code)]
+pub fn totient_sum(x: u64) -> u128 {
+    let limit = sieve_limit(x);
+    let mut phi: Vec<i64> = divisor_sieve::totients(limit)
+        .into_iter()
+        .map(|t| t as i64)
+        .collect();
+    phi[0] = 0;
+    for i in 1..phi.len() {
+        phi[i] += phi[i - 1];
+    }
+    hyperbola(x, &phi, |v| v as i128 * (v as i128 + 1) / 2) as u128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::primes::Sieve;
+    use super::*;
+
+    #[test]
+    pub fn prime_count_test() {
+        let sieve = Sieve::new(100_000);
+        let mut sum = 0;
+        let mut primes = sieve.primes().peekable();
+        for x in 0..=20_000u64 {
+            while primes.peek().is_some_and(|&p| p as u64 <= x) {
+                sum += primes.next().unwrap() as u128;
+            }
+            assert_eq!(
+                prime_count(x),
+                sieve.prime_pi(x as usize) as u64,
+                "x = {}",
+                x
+            );
+            assert_eq!(prime_sum(x), sum);
+        }
+        assert_eq!(prime_count(100_000_000), 5_761_455);
+        let total: u128 = Sieve::new(10_000_000).primes().map(|p| p as u128).sum();
+        assert_eq!(prime_sum(10_000_000), total);
+        assert_eq!(prime_count(10_000_000_000), 455_052_511);
+    }
+    #[test]
+    pub fn mertens_test() {
+        let mu = divisor_sieve::mobius(100_000);
+        let mut m = 0;
+        for x in 1..=3_000u64 {
+            m += mu[x as usize] as i64;
+            assert_eq!(mertens(x), m);
+        }
+        let m: i64 = mu.iter().skip(1).map(|&u| u as i64).sum();
+        assert_eq!(mertens(100_000), m);
+        assert_eq!(mertens(0), 0);
+        assert_eq!(mertens(10_000_000_000), -33_722);
+    }
+    #[test]
+    pub fn totient_sum_test() {
+        let phi = divisor_sieve::totients(100_000);
+        let mut s = 0;
+        for x in 1..=3_000u64 {
+            s += phi[x as usize] as u128;
+            assert_eq!(totient_sum(x), s);
+        }
+        let s: u128 = phi.iter().skip(1).map(|&t| t as u128).sum();
+        assert_eq!(totient_sum(100_000), s);
+        // Euler problem 72, the reduced fractions with denominator <= 10^6 (excluding 1/1)
+        assert_eq!(totient_sum(1_000_000) - 1, 303_963_552_391);
+    }
+}
@@ -4,11 +4,14 @@
 //! we get 3, 5, 6 and 9. The sum of these multiples is 23.
 //! Find the sum of all the multiples of 3 or 5 below 1000.
 
+use super::math::multiples;
+
 /// Euler Problem # 1
 ///
 /// Find the sum of all the multiples of 3 or 5 below 1000.
 pub fn answer() -> u64 {
-    option4(1000)
+    //option4(1000)
+    option5(1000)
 }
 
 /// Euler Problem # 1 (Test Sample)
@@ -20,6 +23,7 @@ pub fn sample() {
     println!("Option2(10) = {}", option2(10));
     println!("Option3(10) = {}", option3(10));
     println!("Option4(10) = {}", option4(10));
+    println!("Option5(10) = {}", option5(10));
 }
 
 /// Find the sum of all the multiples of 3 or 5 below n.
@@ -100,6 +104,23 @@ fn option4(n: u64) -> u64 {
     sum3 + sum5 - sum15
 }
 
+/// Find the sum of all the multiples of 3 or 5 below n.
+///
+/// Option #5: The same as option #4, but each sum is an arithmetic series,
+/// 3 + 6 + ... + 3m = 3m(m+1)/2, so nothing is iterated.
+/// math::multiples does this for any set of divisors, with inclusion-exclusion
+/// over their lcms, so it also works for n up to 10^18.
+///
+/// # Examples
+///
+/// ```
+/// let answer = option5(10);
+/// assert_eq!(answer, 23);
+/// ```
+fn option5(n: u64) -> u64 {
+    multiples::multiples_below(&[3, 5], n).1 as u64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,4 +141,11 @@ mod tests {
     pub fn test_3_iterators() {
         assert_eq!(option4(10), 23);
     }
+    #[test]
+    pub fn test_closed_form() {
+        assert_eq!(option5(10), 23);
+        for n in 0..2000 {
+            assert_eq!(option5(n), option2(n));
+        }
+    }
 }
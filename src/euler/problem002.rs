@@ -12,7 +12,8 @@ use super::math::fibonacci;
 ///
 /// Find the sum of the even Fibonacci numbers not exceeding four million.
 pub fn answer() -> u64 {
-    option3(4_000_000) as u64
+    //option3(4_000_000) as u64
+    option4(4_000_000) as u64
 }
 
 /// Euler Problem # 2 (Test Sample)
@@ -37,6 +38,7 @@ pub fn sample() {
     println!("  Option #1 {}", option1(89));
     println!("  Option #2 {}", option2(89));
     println!("  Option #3 {}", option3(89));
+    println!("  Option #4 {}", option4(89));
 }
 
 // See the fibonacci module for benchmarking of the fibonacci methods
@@ -108,6 +110,23 @@ pub fn option3(n: usize) -> usize {
     sum
 }
 
+/// Sum of even Fibonacci numbers upto n
+///
+/// Option #4:  Solve in closed form, without generating the terms
+/// Every third Fibonacci number is even, and the sum of every third term has a
+/// closed form, so only the index of the last term is needed.
+/// This takes O(log n) steps, but n is too small for it to beat the iterator.
+///
+/// # Examples
+///
+/// ```
+/// let answer = option4(89);
+/// assert_eq!(answer, 44);
+/// ```
+pub fn option4(n: usize) -> usize {
+    fibonacci::fib_sum_even(fibonacci::fib_index(n as u64)) as usize
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,4 +143,11 @@ mod tests {
     pub fn test_loop() {
         assert_eq!(option3(100), 44);
     }
+    #[test]
+    pub fn test_closed_form() {
+        assert_eq!(option4(100), 44);
+        for n in 2..10_000 {
+            assert_eq!(option4(n), option1(n));
+        }
+    }
 }
@@ -4,6 +4,7 @@
 //! What is the largest prime factor of the number 600851475143 ?
 
 use super::math;
+use super::math::{primality, primes};
 
 /// Euler Problem # 3
 ///
@@ -20,14 +21,17 @@ pub fn answer() -> u64 {
 pub fn sample() {
     println!("  Option #1 = {}", option1(13195));
     println!("  Option #2 = {}", option2(13195));
+    println!("  Option #3 = {}", option3(13195));
 }
 
 /// What is the largest prime factor of n
 ///
 /// Option 1, search the list of primes from
-/// 2 .. sqrt(n) in reverse order until one
-/// is divisible.  If none is divisible, return n (prime)
-///
+/// sqrt(n) .. 2 in reverse order, dividing n by each
+/// prime factor as it is found.  The first divisor found is
+/// the largest prime factor below sqrt(n).  There can be at most
+/// one prime factor larger than sqrt(n), and it is whatever is
+/// left after dividing out all the small factors.
 ///
 /// # Examples
 /// ```
@@ -37,16 +41,22 @@ pub fn sample() {
 /// assert_eq!(option1(13195), 29)
 /// ```
 pub fn option1(n: usize) -> usize {
-    // Unfortunately, primal::Sieve::new().primes_from()
-    // is a single ended iterator and cannot be reversed
-    // we need to collect it as a vector to reverse it.
-    let primes: Vec<usize> = primal::Sieve::new(math::isqrt(n)).primes_from(2).collect();
-    for p in primes.iter().rev() {
-        if n % *p == 0 {
-            return *p;
+    // primal::Sieve::new().primes_from() is a single ended iterator and
+    // cannot be reversed, but the iterator from math::primes::Sieve can.
+    let sieve = primes::Sieve::new(math::isqrt(n));
+    let mut largest = None;
+    let mut factor = n;
+    for p in sieve.primes().rev() {
+        while factor.is_multiple_of(p) {
+            factor /= p;
+            largest.get_or_insert(p);
         }
     }
-    n
+    if factor > 1 {
+        factor
+    } else {
+        largest.unwrap_or(n)
+    }
 }
 
 /// What is the largest prime factor of n
@@ -87,6 +97,26 @@ pub fn option2(n: usize) -> usize {
     factor
 }
 
+/// What is the largest prime factor of n
+///
+/// Option 3, factor n with Pollard's rho algorithm (see math::primality).
+/// This does not need a sieve at all, so it works for any 64 bit number,
+/// including a semiprime with two large factors, where the sieve for the
+/// solutions above would need primes up to 2^32.
+/// With a release build, this takes a few microseconds.
+///
+/// # Examples
+/// ```
+/// assert_eq!(option3(6), 3)
+/// assert_eq!(option3(13195), 29)
+/// ```
+pub fn option3(n: u64) -> u64 {
+    match primality::factor(n).last() {
+        Some(&(p, _)) => p,
+        None => n,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,4 +153,13 @@ mod tests {
     pub fn gpf_of_13195a() {
         assert_eq!(option2(13195), 29)
     }
+    #[test]
+    pub fn gpf_option3() {
+        assert_eq!(option3(6), 3);
+        assert_eq!(option3(11), 11);
+        assert_eq!(option3(27), 3);
+        assert_eq!(option3(13195), 29);
+        assert_eq!(option3(600_851_475_143), 6857);
+        assert_eq!(option3(4_294_967_291 * 4_294_967_279), 4_294_967_291);
+    }
 }
@@ -4,6 +4,7 @@
 //! The largest palindrome made from the product of two 2-digit numbers is 9009 = 91 × 99.
 //!
 use super::math;
+use super::math::digits;
 
 /// Problem 4
 ///
@@ -43,48 +44,16 @@ pub fn sample() -> u64 {
 
 /// Creates a palindrome from a number
 ///
-/// The output is concat(n, rev(n))
+/// The output is concat(n, rev(n)).  Note that this is not digits::concat,
+/// since the reversed number loses the zeros, i.e. rev(100) = 1.
 ///
 /// # Examples
 /// ```
 /// assert_eq!(create_palindrome(1234), 12344321)
 /// ```
 fn create_palindrome(x: usize) -> usize {
-    let n = number_of_digits(x);
-    x * 10_usize.pow(n) + reverse_number(x)
-}
-
-/// Returns a number
-///
-/// # Examples
-/// ```
-/// assert_eq!(reverse_number(678), 876)
-/// ```
-fn reverse_number(x: usize) -> usize {
-    let n = number_of_digits(x);
-    (1..=n)
-        .scan(x, |y, _| {
-            let r = *y % 10;
-            *y /= 10;
-            Some(r)
-        }) // turns 543 into [3,4,5]
-        .fold(0, |a, y| a * 10 + y) //turns [3,4,5] into 345
-}
-
-/// Returns the number of digits in a number
-///
-/// # Examples
-/// ```
-/// assert_eq!(number_of_digits(777), 3)
-/// ```
-fn number_of_digits(x: usize) -> u32 {
-    let mut x = x;
-    let mut n = 1;
-    while x > 10 {
-        x /= 10;
-        n += 1;
-    }
-    n
+    let n = digits::count(x, 10);
+    x * 10_usize.pow(n) + digits::reverse(x, 10)
 }
 
 /// Is a number is divisible by two 3 digit numbers?
@@ -121,18 +90,6 @@ fn is_divisible_by_2digits(x: usize) -> bool {
 mod tests {
     use super::*;
 
-    #[test]
-    pub fn test_number_of_digits_1() {
-        assert_eq!(number_of_digits(0), 1);
-    }
-    #[test]
-    pub fn test_number_of_digits_6() {
-        assert_eq!(number_of_digits(12345678), 8);
-    }
-    #[test]
-    pub fn test_reverse_number() {
-        assert_eq!(reverse_number(12345678), 87654321);
-    }
     #[test]
     pub fn test_is_divisible_by_2digits() {
         assert!(is_divisible_by_2digits(9009));
@@ -152,6 +109,8 @@ mod tests {
     #[test]
     pub fn test_palindrome() {
         assert_eq!(create_palindrome(987), 987_789);
+        assert_eq!(create_palindrome(100), 100_001);
+        assert!(digits::is_palindrome(create_palindrome(12345), 10));
     }
     #[test]
     pub fn test_sample() {
@@ -14,13 +14,16 @@
 //! Find the difference between the sum of the squares of the first one hundred
 //! natural numbers and the square of the sum.
 
+use super::math::power_sums;
+
 /// Problem 6
 ///
 /// Find the difference between the sum of the squares of the first one hundred
 /// natural numbers and the square of the sum.
 ///
 pub fn answer() -> u64 {
-    option1(100) as u64
+    //option1(100) as u64
+    option2(100) as u64
 }
 
 /// Problem 5 (Test Sample)
@@ -51,6 +54,16 @@ fn sum_1_to(n: usize) -> usize {
     (n + 1) * n / 2
 }
 
+/// Difference between the sum of the squares of the first n
+/// natural numbers and the square of the sum.
+///
+/// Both sums are power sums, 1^k + 2^k + ... + n^k, with k = 1 and 2, which
+/// math::power_sums evaluates in closed form (Faulhaber's formula).
+fn option2(n: u64) -> u128 {
+    let sum = power_sums::power_sum(n, 1);
+    sum * sum - power_sums::power_sum(n, 2)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -59,4 +72,10 @@ mod tests {
     pub fn test_sample() {
         assert_eq!(sample(), 2640);
     }
+    #[test]
+    pub fn test_power_sums() {
+        for n in 1..=1000 {
+            assert_eq!(option2(n as u64), option1(n) as u128);
+        }
+    }
 }
@@ -25,13 +25,17 @@
 //! NOTE: This problem is an easier version of Problem 110;
 //! it is strongly advised that you solve this one first.
 
+use super::math::arith;
+use super::math::Ratio;
+
 /// Problem 108
 ///
 /// What is the least value of n for which the number of distinct solutions exceeds one-thousand?
 ///
 pub fn answer() -> u64 {
     //option1(1000, 1000)  // takes about 14 seconds (optimized)
-    option1(180000, 1000)
+    //option1(180000, 1000)
+    option2(1000)
 }
 
 /// Problem 5 (Test Sample)
@@ -51,6 +55,38 @@ pub fn diophantine_solutions(n: u64) -> u64 {
     (1..=n).filter(|i| n2 % i == 0).count() as u64
 }
 
+/// Problem 108 (Test Sample)
+///
+/// Print the distinct solutions for n = 4, as fractions
+#[allow(dead_code)]
+pub fn sample() {
+    let n = 4;
+    for (x, y) in solutions(n) {
+        let sum = Ratio::new(1, x as i64) + Ratio::new(1, y as i64);
+        println!("1/{} + 1/{} = {}", x, y, sum);
+    }
+}
+
+/// Returns the distinct solutions (x, y) for n, with x <= y
+///
+/// For each x in (n+1..=2n), 1/y = 1/n - 1/x is computed exactly, and it is a
+/// solution when the numerator of the reduced fraction is 1.  This does not
+/// rely on the divisor analysis in diophantine_solutions(), so it can be used to
+/// check it.
+pub fn solutions(n: u64) -> Vec<(u64, u64)> {
+    let one_over_n = Ratio::new(1, n as i64);
+    (n + 1..=2 * n)
+        .filter_map(|x| {
+            let one_over_y = one_over_n - Ratio::new(1, x as i64);
+            if one_over_y.numer() == 1 {
+                Some((x, one_over_y.denom() as u64))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 /// Prints successive maximum solutions
 ///
 /// Originally I started at 4 and stepped by 1, but it became
@@ -83,6 +119,7 @@ pub fn find_trend(n1: u64, n2: u64) {
 /// based on the trend analysis, I know if I start with a multiple of
 /// 10, I can step by 10.  This invariant is not checked by the code,
 /// but enforced by the caller.
+#[allow(dead_code)]
 fn option1(start: u64, goal: u64) -> u64 {
     let mut answer = 0_u64;
     for i in (start..).step_by(10) {
@@ -95,6 +132,33 @@ fn option1(start: u64, goal: u64) -> u64 {
     answer
 }
 
+/// Number of distinct solutions for n
+///
+/// Per the analysis in diophantine_solutions(), the solutions are the divisors i
+/// of n*n where i <= n.  The divisors of n*n come in pairs (i, n*n/i), except for n
+/// itself, so the number of solutions is (d(n*n) + 1)/2, where d is the divisor count.
+/// The factorization of n*n is the factorization of n with each exponent doubled.
+pub fn diophantine_solutions2(n: u64, sieve: &primal::Sieve) -> u64 {
+    let factors: Vec<(usize, usize)> = sieve
+        .factor(n as usize)
+        .unwrap()
+        .iter()
+        .map(|&(p, e)| (p, 2 * e))
+        .collect();
+    (arith::divisor_count(&factors) as u64).div_ceil(2)
+}
+
+/// Find the smallest number that has more solutions than goal.
+///
+/// Counting the divisors from the factorization is fast enough that we can
+/// check every number starting at 1, without the trend analysis.
+fn option2(goal: u64) -> u64 {
+    let sieve = primal::Sieve::new(1_000_000);
+    (1..)
+        .find(|&i| diophantine_solutions2(i, &sieve) > goal)
+        .unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,4 +167,28 @@ mod tests {
     pub fn test_sample() {
         assert_eq!(diophantine_solutions(4), 3);
     }
+    #[test]
+    pub fn test_solutions() {
+        assert_eq!(solutions(4), vec![(5, 20), (6, 12), (8, 8)]);
+        for n in 1..300 {
+            let pairs = solutions(n);
+            assert_eq!(pairs.len() as u64, diophantine_solutions(n));
+            let one_over_n = Ratio::new(1, n as i64);
+            for (x, y) in pairs {
+                assert!(x <= y);
+                assert_eq!(
+                    Ratio::new(1, x as i64) + Ratio::new(1, y as i64),
+                    one_over_n
+                );
+            }
+        }
+    }
+    #[test]
+    pub fn test_sample2() {
+        let sieve = primal::Sieve::new(1000);
+        assert_eq!(diophantine_solutions2(4, &sieve), 3);
+        for i in 1..1000 {
+            assert_eq!(diophantine_solutions2(i, &sieve), diophantine_solutions(i));
+        }
+    }
 }
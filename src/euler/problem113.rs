@@ -15,6 +15,10 @@
 //!
 //! How many numbers below a googol (10^100) are not bouncy?
 
+use super::math::figurate;
+use super::math::BigUint;
+use std::ops::{Add, Mul};
+
 /// Problem 113
 ///
 /// How many numbers below a googol (10^100) are not bouncy?
@@ -88,59 +92,62 @@ pub fn sample() {
 ///
 /// # Panics
 ///
-/// will panic if exp is zero or greater than 100
+/// will panic if exp is zero, or if the count does not fit in a usize
+/// (use non_bouncy_big for that)
 pub fn non_bouncy(exp: usize) -> usize {
-    // assert!(exp > 0 && exp < 101);
+    non_bouncy_count(exp)
+}
+
+/// Return the number of non-bouncy numbers below 10^exp for any exp
+///
+/// The same as non_bouncy, but with arbitrary precision.
+///
+/// # Examples
+/// ```
+/// assert_eq!(non_bouncy_big(100).to_string(), "51161058134250");
+/// ```
+#[allow(dead_code)]
+pub fn non_bouncy_big(exp: usize) -> BigUint {
+    non_bouncy_count(exp)
+}
+
+/// The implementation of non_bouncy for any integer type
+///
+/// see non_bouncy for the formula
+fn non_bouncy_count<T>(exp: usize) -> T
+where
+    T: Clone + From<u8> + Add<Output = T> + Mul<Output = T>,
+{
+    assert!(exp > 0, "exp must be positive");
     // per the formula above, I will need the polytopic numbers p(x,y) where
     // x in 1..9 inclusive and y in 1..(n-x) inclusive.  I can save a lot of
-    // effort by calculating these once and caching them in a 9xn-1 matrix
+    // effort by calculating these once and caching them in a 9xw matrix
     // note that is matrix has base zero indices, where as my formula are all
     // base 1 indices.  remember to subtract 1 for all matrix accesses.
-    // I am also creating a fixed size array because access will be much faster
-    // than a variable sized vector on the heap.
-    // Also note that these numbers can get very large, and without bounds on
-    // size of the matrix, I would need to use a "Big Integer" type.
-    let mut p = [[0usize; 99]; 9];
-    for i in 0..9 {
-        p[i][0] = 1;
-    }
-    for j in 1..99 {
-        p[0][j] = p[0][j - 1] + 1;
-    }
-    for i in 1..9 {
-        for j in 1..99 - i {
-            //p[i][j] = super::math::polytopic_number(i + 1, j + 1);
-            // super::math::polytopic_number(i,j) is very fast at generating a single number,
-            // but since I need to memoize all the numbers, I can use the pattern of
-            // of the previously generated numbers
-            // 1:   1 2  3  4  5  6
-            // 2:   1 3  6 10 15 21
-            // 3:   1 4 10 20 35 56
-            // 4:   1 5 15 35 91
-            // 5:   1 6 21 56
-            p[i][j] = p[i][j - 1] + p[i - 1][j];
-        }
-    }
+    // These numbers can get very large, which is why T may be a BigUint.
+    let w = exp.max(10) - 1;
+    let p: Vec<Vec<T>> = figurate::polytopic_table(9, w);
     // n is the length of the number i.e. 123456 = 6
     // There are 9 non-bouncy nubmers below 10 (n = 1)
-    let mut sum: usize = 9; // for n = 1
-                            // we start iterating at n = 2
-                            // We could save some effort by using the given count for 10^10 and start at n = 11
-                            // but this allows us to test our solution.
+    let mut sum = T::from(9); // for n = 1
+                              // we start iterating at n = 2
+                              // We could save some effort by using the given count for 10^10 and start at n = 11
+                              // but this allows us to test our solution.
     for n in 2..=exp {
         // There are always 9 numbers at each n that are not increasing or decreasing
-        sum += 9;
+        sum = sum + T::from(9);
         // d is the number of distinct digits in the number i.e. 2244555 = 3
         let d_limit = n.min(10);
         for d in 2..=d_limit {
             let more = if d == 10 {
                 // with base 1 index: p(9,n-9)
-                p[8][n - 10]
+                p[8][n - 10].clone()
             } else {
                 // with base 1-index: [p(d,10-d) + p(d,11-d)] * p(d-1,n+1-d)
-                (p[d - 1][9 - d] + p[d - 1][10 - d]) * p[d - 2][n - d] //base 0
+                (p[d - 1][9 - d].clone() + p[d - 1][10 - d].clone()) * p[d - 2][n - d].clone()
+                //base 0
             };
-            sum += more;
+            sum = sum + more;
         }
     }
     sum
@@ -162,4 +169,17 @@ mod tests {
     pub fn non_bouncy_test_10() {
         assert_eq!(non_bouncy(10), 277032);
     }
+    #[test]
+    pub fn non_bouncy_big_test() {
+        assert_eq!(non_bouncy_big(100), BigUint::from(non_bouncy(100)));
+        // closed form: C(n+10,10) + C(n+9,9) - 2 - 10n
+        let binomial = |n: u32, k: u32| {
+            (1..=k).fold(BigUint::one(), |c, i| {
+                c.mul_small(n - k + i).div_rem_small(i).0
+            })
+        };
+        let n = 1000;
+        let expected = binomial(n + 10, 10) + binomial(n + 9, 9) - BigUint::from(2 + 10 * n);
+        assert_eq!(non_bouncy_big(n as usize), expected);
+    }
 }
@@ -10,6 +10,8 @@
 //! You are given that a2 = 512 and a10 = 614656.
 //! Find a30.
 
+use super::math::digits;
+
 /// Problem 119
 ///
 /// Find a30
@@ -33,7 +35,7 @@ pub fn sample() {
     for i in 6..150_usize {
         for j in 2..100 {
             let p = i.pow(j);
-            if sum_of_digits(p) == i {
+            if digits::sum(p, 10) == i {
                 println!("{}^{} = {}", i, j, p)
             }
             if p > max {
@@ -55,7 +57,7 @@ pub fn option1(n: usize) -> usize {
     for i in 7..70_usize {
         for j in 2..10 {
             let p = i.pow(j);
-            if sum_of_digits(p) == i {
+            if digits::sum(p, 10) == i {
                 v.push(p)
             }
         }
@@ -64,40 +66,10 @@ pub fn option1(n: usize) -> usize {
     v[n - 1]
 }
 
-/// Returns the sum of a numbers digits
-///
-/// # Examples
-/// ```
-/// assert_eq!(sum_of_digits(78), 15)
-/// assert_eq!(sum_of_digits(102000), 3)
-/// ```
-fn sum_of_digits(x: usize) -> usize {
-    let mut operand = x;
-    let mut solution = 0;
-    while operand > 9 {
-        solution += operand % 10;
-        operand /= 10;
-    }
-    solution += operand;
-    solution
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    pub fn sum_of_digits_test1() {
-        assert_eq!(sum_of_digits(4), 4);
-    }
-    #[test]
-    pub fn sum_of_digits_test2() {
-        assert_eq!(sum_of_digits(78), 15);
-    }
-    #[test]
-    pub fn sum_of_digits_test3() {
-        assert_eq!(sum_of_digits(102000), 3);
-    }
     #[test]
     pub fn option1_test1() {
         assert_eq!(option1(2), 512);
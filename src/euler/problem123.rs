@@ -9,6 +9,8 @@
 //! 
 //! Find the least value of n for which the remainder first exceeds 10^10.
 
+use super::math::modular;
+
 /// Problem 123
 ///
 /// Find the least value of n for which the remainder first exceeds 10^10
@@ -23,6 +25,7 @@ pub fn answer() -> u64 {
 #[allow(dead_code)]
 pub fn sample() {
     println!("pn with rem > 10^4 = {}", option1(1, 10_usize.pow(4)));
+    println!("(p3-1)^3 + (p3+1)^3 mod p3^2 = {}", remainder(5, 3));
 }
 
 /// Find n (for the nth prime) with remainder greater than min_rem
@@ -70,6 +73,23 @@ pub fn option1(start: usize, min_rem: usize) -> usize {
     return 0  //We will never get here but compiler doesn't know that
 }
 
+/// Returns the remainder when (p−1)^n + (p+1)^n is divided by p^2
+///
+/// This computes the remainder directly with modular exponentiation, it is
+/// used to check the closed form (2np for odd n, 2 for even n) derived above.
+/// The modulus p^2 must fit in a u64 for modular::pow_mod.
+///
+/// # Panics
+///
+/// will panic if p >= 2^32
+pub fn remainder(p: u64, n: u64) -> u64 {
+    assert!(p < 1 << 32, "p^2 must fit in a u64");
+    let p2 = p * p;
+    // each term is below p^2, but their sum can be close to 2^65
+    let sum = modular::pow_mod(p - 1, n, p2) as u128 + modular::pow_mod(p + 1, n, p2) as u128;
+    (sum % p2 as u128) as u64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,4 +98,20 @@ mod tests {
     pub fn prime_square_remainders_test1() {
         assert_eq!(option1(1, 10_usize.pow(9)), 7037);
     }
+    #[test]
+    pub fn prime_square_remainders_formula_test() {
+        assert_eq!(remainder(5, 3), 5);
+        // the largest prime below 2^32
+        assert_eq!(remainder(4_294_967_291, 3), 6 * 4_294_967_291);
+        // where the two terms add up to more than u64::MAX
+        let p: u64 = 4_294_967_291;
+        let n = p - 2;
+        let expected = 2 * ((n % p) as u128 * p as u128) % (p as u128 * p as u128);
+        assert_eq!(remainder(p, n) as u128, expected);
+        // after n = 3, 2n < pn, so the remainder is 2*n*pn for odd n
+        for (p, n) in primal::Primes::all().zip(1..).skip(3).take(1000) {
+            let expected = if n % 2 == 1 { 2 * n * p } else { 2 };
+            assert_eq!(remainder(p as u64, n as u64), expected as u64);
+        }
+    }
 }
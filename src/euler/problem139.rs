@@ -15,12 +15,16 @@
 //! Given that the perimeter of the right triangle is less than one-hundred million,
 //! how many Pythagorean triangles would allow such a tiling to take place?
 
+use super::math::pell;
+use super::math::pythagorean::{self, Bound};
+
 /// Problem 139
 ///
 /// Given that the perimeter of the right triangle is less than one-hundred million,
 /// how many Pythagorean triangles would allow such a tiling to take place?
 pub fn answer() -> u64 {
-    option1(100_000_000) as u64
+    //option1(100_000_000) as u64
+    option2(100_000_000) as u64
 }
 
 /// Problem 139 (Test Sample)
@@ -34,56 +38,44 @@ pub fn sample() {
 /// Returns the number of "tile-able" pythagorean triangles with perimeter < n
 ///
 /// We generate a list of primative pythagorean triples using Euclid's formula
-/// for an arbitrary pair of integers m and n with m > n > 0. The formula states that the integers
-///
-///     a = m^2-n^2,  b = 2mn,  c = m^2+n^2
-///
-/// form a Pythagorean triple. The triple generated by Euclid's formula is primitive if and
-/// only if m and n are coprime and not both odd. When both m and n are odd, then a, b, and c
-/// will be even, and the triple will not be primitive; however, dividing a, b, and c by 2
-/// will yield a primitive triple when m and n are coprime and both odd.
+/// (see math::pythagorean).  The hole in the middle of the square is |b-a| by |b-a|,
+/// so the square can be tiled when the hole size divides c.
 ///
 /// Since we are only getting primatives, we also need to count all multiples of the primative
 /// i.e. since 3,4,5 works, 6,8,10 also works.
 ///
-/// Uncomment the println! statements to see which triples are being tested.  Useful for testing.
+/// The perimeter must be strictly less than n, so the bound is a perimeter of at most n-1.
 pub fn option1(a_b_c: usize) -> usize {
+    let limit = a_b_c.saturating_sub(1);
+    pythagorean::primitive_triples(Bound::Perimeter(limit as u64))
+        .filter(|t| t.c % (t.b - t.a) == 0)
+        // This triple and all multiples satisfy the problem
+        .map(|t| limit / t.perimeter() as usize)
+        .sum()
+}
+
+/// Returns the number of "tile-able" pythagorean triangles with perimeter < n
+///
+/// From option1, a primitive triple tiles the square when the hole size |b-a|
+/// divides c.  Since (b-a)^2 + 2ab = c^2, any common factor of b-a and c also
+/// divides 2ab, which is impossible for a primitive triple (c is odd and coprime
+/// to a and b), so the hole must be 1, and b = a + 1.
+///
+///     a^2 + (a+1)^2 = c^2  =>  (2a+1)^2 - 2c^2 = -1
+///
+/// This is a negative Pell equation with x = 2a+1, y = c, so the primitive
+/// triangles are the Pell solutions, and the perimeter is a + (a+1) + c = x + y.
+/// The solutions grow by a factor of about 5.8, so there are only a handful.
+pub fn option2(a_b_c: usize) -> usize {
     let mut total = 0;
-    // a + b + c = (m^2 - n^2) + 2mn + (m^2 + n^2) = 2m^2 + 2mn = 2m(m+n)
-    for m in 2.. {
-        let m2 = m * m;
-        let perim = 2 * (m2 + m); // assuming n = 1
-        if perim > a_b_c {
+    // the first solution (1, 1) is the degenerate triangle (0, 1, 1)
+    for (x, y) in pell::negative_solutions(2).skip(1) {
+        let perim = (&x + &y).to_u64().unwrap() as usize;
+        if perim >= a_b_c {
             break;
         }
-        for n in 1..m {
-            //println!("(m,n) = ({},{})", m, n);
-            let perim = 2 * (m2 + m * n);
-            if perim > a_b_c {
-                break;
-            }
-            let odd = (m - n) % 2 == 1;
-            if odd && num_integer::gcd(m, n) == 1 {
-                // primative pythagorean triple
-                let n2 = n * n;
-                let a = m2 - n2;
-                let b = 2 * m * n;
-                let c = m2 + n2;
-                //println!("Right triangle {},{},{}", a, b, c);
-                let hole_size;
-                if a > b {
-                    hole_size = a - b;
-                } else {
-                    hole_size = b - a;
-                }
-                if c % hole_size == 0 {
-                    // This triple and all multiples satisfy the problem
-                    let count = a_b_c / perim;
-                    total += count;
-                    //println!("   {} hole paves square!", count)
-                }
-            }
-        }
+        // This triple and all multiples satisfy the problem
+        total += (a_b_c - 1) / perim;
     }
     total
 }
@@ -96,4 +88,15 @@ mod tests {
     pub fn sum_of_digits_test1() {
         assert_eq!(option1(100), 9);
     }
+    #[test]
+    pub fn option2_test() {
+        for &n in [100, 1000, 12345, 1_000_000].iter() {
+            assert_eq!(option2(n), option1(n));
+        }
+        // the bound is a perimeter of 3-4-5 (12), 20-21-29 (70) or a multiple, which is not counted
+        for &(n, count) in [(12, 0), (13, 1), (24, 1), (25, 2), (70, 5), (71, 6)].iter() {
+            assert_eq!(option1(n), count, "n = {}", n);
+            assert_eq!(option2(n), count, "n = {}", n);
+        }
+    }
 }
@@ -5,14 +5,15 @@
 //!
 //! For example, 14 has the positive divisors 1, 2, 7, 14 while 15 has 1, 3, 5, 15.
 
-use super::math::isqrt;
+use super::math::divisor_sieve;
+use super::math::spf::{SpfEntry, SpfSieve};
 
 /// Problem 179
 ///
 /// Find the number of integers 1 < n < 10^7, for which n and n + 1 have the
 /// same number of positive divisors.
 pub fn answer() -> u64 {
-    option1(10_000_000) as u64
+    option2(10_000_000) as u64
 }
 
 /// Problem 179 (Test Sample)
@@ -23,10 +24,11 @@ pub fn answer() -> u64 {
 pub fn sample() {
     print_divisor_counts(25);
     println!("{} pairs below 25", option1(25));
+    println!("{} pairs below 25", option2(25));
 }
 
 pub fn print_divisor_counts(n: usize) {
-    let sieve = primal::Sieve::new(isqrt(n));
+    let sieve = SpfSieve::new(n + 1);
     for i in 2..=(n + 1) {
         println!("{} has {} divisors", i, divisor_count(i, &sieve));
     }
@@ -34,9 +36,10 @@ pub fn print_divisor_counts(n: usize) {
 
 /// Returns the count of consecutive numbers with the same divisor count
 ///
-/// Uses the prime sieve from the primal crate
+/// Uses a smallest prime factor sieve to factor each number.  This is
+/// much faster than the trial division done by primal::Sieve::factor()
 pub fn option1(n: usize) -> usize {
-    let sieve = primal::Sieve::new(n);
+    let sieve = SpfSieve::compact(n + 1);
     let mut count = 0;
     let mut prev = 0;
     for i in 2..=(n + 1) {
@@ -49,6 +52,15 @@ pub fn option1(n: usize) -> usize {
     count
 }
 
+/// Returns the count of consecutive numbers with the same divisor count
+///
+/// Builds a table of the divisor counts of every number up to n+1 in a
+/// single pass, and then scans the table for matching neighbors.
+pub fn option2(n: usize) -> usize {
+    let d = divisor_sieve::divisor_counts(n + 1);
+    (2..=n).filter(|&i| d[i] == d[i + 1]).count()
+}
+
 /// Count the divisors of n
 ///
 /// The count includes 1 and the n
@@ -59,22 +71,10 @@ pub fn option1(n: usize) -> usize {
 /// prime factor occurs
 ///
 /// Since we will call this many times, we have the caller generate a
-/// sieve of prime numbers that can be used to find the divisors
-pub fn divisor_count(n: usize, sieve: &primal::Sieve) -> usize {
-    if sieve.is_prime(n) {
-        return 2;
-    }
-    let mut total = 1;
-    // factor() returns a Result object; I'll panic if it is an Err()
-    // It should only error if I don't give it enough primes, so it should be ok.
-    // The result is a vector of (prime#, count) tuples.
-    // I could write my own prime factor finders/counter, but I will need a list
-    // of prime numbers to do it, so it won't be any faster than this solution.
-    let factors = sieve.factor(n).unwrap();
-    for (_, c) in factors.iter() {
-        total *= c + 1;
-    }
-    total
+/// sieve of smallest prime factors that can be used to find the divisors
+pub fn divisor_count<T: SpfEntry>(n: usize, sieve: &SpfSieve<T>) -> usize {
+    // factors() returns an iterator of (prime#, count) tuples.
+    sieve.factors(n).map(|(_, c)| c + 1).product()
 }
 
 #[cfg(test)]
@@ -85,4 +85,9 @@ mod tests {
     pub fn divisor_count_test1() {
         assert_eq!(option1(25), 3);
     }
+    #[test]
+    pub fn divisor_count_test2() {
+        assert_eq!(option2(25), 3);
+        assert_eq!(option2(10_000), option1(10_000));
+    }
 }
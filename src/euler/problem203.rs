@@ -24,6 +24,8 @@
 //! Find the sum of the distinct squarefree numbers in the first 51 rows of
 //! Pascal's triangle.
 
+use super::math::combinatorics;
+use num_integer::Roots;
 use std::collections::HashSet;
 
 /// Problem 203
@@ -31,7 +33,8 @@ use std::collections::HashSet;
 /// Find the sum of the distinct squarefree numbers in the first 51 rows of
 /// Pascal's triangle.
 pub fn answer() -> u64 {
-    sum_square_free_pascal(51) as u64
+    //sum_square_free_pascal(51) as u64
+    option2(51)
 }
 
 /// Problem 203 (Test Sample)
@@ -52,56 +55,75 @@ pub fn sample() {
 /// Pascal's triangle.
 ///
 /// This is a simple brute force solution.  Generate the elements of pascals triangle
-/// and if it has not been considered, then check if it is a multiple of any
-/// the squared primes less than itself.
+/// and put them in a set to remove the duplicates, then check if each one is a multiple
+/// of any the squared primes less than itself.
 ///
-/// There are only (51 + 1)*51/2 = 26*51 = 1326 elements to check, I can skip all the 1s
-/// which reduces this to 1225 elements, which is a very small set of numbers to check.
-/// I could impove this further, as the triangle is symetrical, so I could skip the hash
-/// lookup by only generating half the triangle, but that complicates things.
+/// There are only (51 + 1)*51/2 = 26*51 = 1326 elements to check, and since the triangle
+/// is symetrical, I only need the first half of each row, which is a very small set of
+/// numbers to check.
 ///
 /// I use the primal::sieve to generate the prime numbers that need to be considered.
 /// As an optimization, I create the sieve once, but I need to know the bigest prime I
-/// will need.  I could do this by printing pascals triangle and manually selecting the
-/// largest number (middle of bottom row).
+/// will need.  This is the square root of the largest number (middle of bottom row).
+///
+/// # Panics
+///
+/// will panic if n > 68, since the numbers in the later rows do not fit in a u64
 fn sum_square_free_pascal(n: usize) -> usize {
-    // To consider a number only once, we put processed numbers in a hashset
-    let mut used: HashSet<usize> = HashSet::new();
+    // To consider a number only once, we put the numbers in a hashset
+    let mut used: HashSet<u64> = HashSet::new();
+    let mut rows = 0;
+    for row in combinatorics::pascal_rows().take(n) {
+        used.extend(row.iter().take(row.len() / 2 + 1));
+        rows += 1;
+    }
+    assert_eq!(rows, n, "row {} of pascals triangle overflows", rows + 1);
     // The largest number in the 51st row is 126410606437752, which is less
     // than 11243248^2, so we will only need primes to 11243248
-    let sieve = primal::Sieve::new(11243248);
-    let mut sum = 1; // This is for the trivial case of only 1 row
-    used.insert(1);
-    // The array size must be a constant, limiting generality of this solution
-    let mut row: [usize; 51] = [1; 51];
-    // skip first two rows, as they are 1 and 1,1, and 1 has already been considered
-    // The following loop also skips the leading and trailing 1 in pascals triangle
-    for row_n in 3..=n {
-        let mut t1 = row[0];
-        let mut t2 = row[1];
-        for i in 1..row_n - 1 {
-            row[i] = t1 + t2;
-            if used.insert(row[i]) {
-                let mut is_squarefree = true;
-                for j in sieve
-                    .primes_from(2)
-                    .map(|p| p * p)
-                    .take_while(|&p| p <= row[i])
-                {
-                    if row[i] % j == 0 {
-                        is_squarefree = false;
-                        break;
-                    }
-                }
-                if is_squarefree {
-                    sum += row[i];
-                }
+    let largest = used.iter().max().cloned().unwrap_or(1);
+    let sieve = primal::Sieve::new(largest.sqrt() as usize + 1);
+    used.iter()
+        .filter(|&&x| {
+            sieve
+                .primes_from(2)
+                .map(|p| (p * p) as u64)
+                .take_while(|&p| p <= x)
+                .all(|p| x % p != 0)
+        })
+        .sum::<u64>() as usize
+}
+
+/// Returns the sum of the distinct squarefree numbers in the first n rows of
+/// Pascal's triangle, without trial division of the large numbers.
+///
+/// By Kummer's theorem, the exponent of p in C(r, k) is the number of carries
+/// when adding k and r-k in base p, so C(r, k) is squarefree if there is at most
+/// one carry for every prime p.  A prime larger than r cannot divide C(r, k), so
+/// only the primes up to 50 need to be checked, rather than a sieve to 11243248.
+///
+/// # Panics
+///
+/// will panic if n > 68, since the numbers in the later rows do not fit in a u64
+fn option2(n: usize) -> u64 {
+    let sieve = primal::Sieve::new(n.max(2));
+    let mut used: HashSet<u64> = HashSet::new();
+    let mut rows = 0;
+    for (r, row) in combinatorics::pascal_rows().take(n).enumerate() {
+        let r = r as u64;
+        for (k, &x) in row.iter().enumerate().take(row.len() / 2 + 1) {
+            let squarefree = sieve
+                .primes_from(2)
+                .map(|p| p as u64)
+                .take_while(|&p| p <= r)
+                .all(|p| combinatorics::binomial_valuation(r, k as u64, p) < 2);
+            if squarefree {
+                used.insert(x);
             }
-            t1 = t2;
-            t2 = row[i + 1]
         }
+        rows += 1;
     }
-    sum
+    assert_eq!(rows, n, "row {} of pascals triangle overflows", rows + 1);
+    used.iter().sum()
 }
 
 /// Prints the first 20 rows of pascals triangle
@@ -109,24 +131,10 @@ fn sum_square_free_pascal(n: usize) -> usize {
 /// Used to test generation algorithm
 #[allow(dead_code)]
 fn pascal() {
-    let n = 20;
-    let mut row: [usize; 20] = [1; 20]; //size must be a constant, limiting generality of solution
-    println!("{}:  {}", 1, row[0]);
-    print!("{}:  {} {}", 2, row[0], row[1]);
-    for row_n in 3..=n {
-        print!("\n{}: ", row_n);
-        print!(" {}", row[0]);
-        let mut t1 = row[0];
-        let mut t2 = row[1];
-        for i in 1..row_n - 1 {
-            row[i] = t1 + t2;
-            print!(" {}", row[i]);
-            t1 = t2;
-            t2 = row[i + 1]
-        }
-        print!(" {}", row[row_n - 1]);
+    for (i, row) in combinatorics::pascal_rows().take(20).enumerate() {
+        let row: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+        println!("{}:  {}", i + 1, row.join(" "));
     }
-    println!("");
 }
 
 /// Prints true/false if the first 20 natural numbers are prime square free
@@ -157,5 +165,12 @@ mod tests {
     #[test]
     pub fn square_free_pascal_test() {
         assert_eq!(sum_square_free_pascal(8), 105);
+        assert_eq!(sum_square_free_pascal(1), 1);
+    }
+    #[test]
+    pub fn option2_test() {
+        for n in 1..=30 {
+            assert_eq!(option2(n), sum_square_free_pascal(n) as u64);
+        }
     }
 }
@@ -7,6 +7,9 @@
 //! such that for every divisor d of n, d+n/d is prime.
 
 use super::math;
+use super::math::{arith, divisors};
+use super::math::spf::SpfSieve;
+use super::math::summatory;
 use std::collections::HashSet;
 
 /// Problem 357
@@ -23,7 +26,7 @@ pub fn answer() -> u64 {
 #[allow(dead_code)]
 pub fn sample() {
     let n = 100;
-    let sieve = primal::Sieve::new(101); //Add 1 to get to check if 1+n/1 is prime
+    let sieve = SpfSieve::compact(101); //Add 1 to get to check if 1+n/1 is prime
     for i in 1..n {
         println!("Divisors of {}: {:?}", i, divisors(i, &sieve));
         println!("Unique Divisors of {}: {:?}", i, unique_divisors(i, &sieve));
@@ -39,8 +42,16 @@ pub fn sample() {
         );
     }
     println!("Sum of n with prime divisors = {}", sum_divisors_to(n));
+    // every candidate n is a prime-1, so these bound the search for n = 10^8
+    let x = 100_000_001;
+    println!(
+        "Candidates p-1 to 10^8 = {}, their sum = {}",
+        summatory::prime_count(x),
+        summatory::prime_sum(x) - summatory::prime_count(x) as u128
+    );
     //println!("divisors of 12 = {:?}", divisors(12, &sieve));
-    //println!("power set of [1,2,3] = {:?}", math::power_set(&vec![1, 2, 3], 0));
+    //let s: Vec<Vec<&usize>> = math::subsets::subsets(&[1, 2, 3]).map(|s| s.collect()).collect();
+    //println!("subsets of [1,2,3] = {:?}", s);
 }
 
 /// Returns the sum of all positive integers not exceeding n
@@ -66,7 +77,8 @@ pub fn sample() {
 ///
 /// By using the fact that 1+n/1 must be prime for n to be considered, I can limit
 /// the search space considerably by only considering n that are a prime-1.  This
-/// is a much smaller search space than the even numbers.
+/// is a much smaller search space than the even numbers.  There are only
+/// π(10^8+1) = 5,761,455 candidates (see summatory::prime_count) instead of 5*10^7.
 ///
 /// For comparison solution takes:
 ///   450 sec searching even numbers and including multiples of squares
@@ -78,8 +90,8 @@ pub fn sample() {
 /// It turns out that processing duplicate divisors, is faster (9sec) than
 /// using a hashset (16sec) to ensure we have a unique set of divisors.
 ///
-/// This solution can still be improved by using a non-recursive algorithm to generate
-/// a unique list of just the small divisors of a number (excluding 1)
+/// This solution was improved by using a non-recursive algorithm (math::divisors) to
+/// generate a unique list of just the small divisors of a number (excluding 1)
 /// 1 can be ignored because 1 and n are always valid since we pick 1 + n to be prime
 /// for the other divisors, if d+n/d = d'+n/d' where d' is the larger divisor that
 /// matchs d, i.e. d' = n/d, so the larger divisors do not need to be checked.
@@ -99,7 +111,9 @@ pub fn sum_divisors_to(n: usize) -> usize {
     // If we are looking at n = p-1 then we will use the sieve to generate the
     // primes to n+1.  Therefore in both cases, we need a large sieve.
 
-    let sieve = primal::Sieve::new(n + 1);
+    // The smallest prime factor sieve provides both the primes and the factors
+    // of n.  The compact (u32) version keeps the 10^8 sieve to about 200MB.
+    let sieve = SpfSieve::compact(n + 1);
 
     // stupid solution
     //(1..=n).filter(|&d| divisors_are_prime(d, &sieve)).sum()
@@ -112,7 +126,7 @@ pub fn sum_divisors_to(n: usize) -> usize {
     // space considerably by only considering n that are a prime-1
     // for this I will need a larger sieve.
     sieve
-        .primes_from(2)
+        .primes()
         .map(|p| p - 1)
         .take_while(|&x| x <= n)
         .filter(|&d| divisors_are_prime(d, &sieve))
@@ -135,7 +149,7 @@ pub fn sum_divisors_to(n: usize) -> usize {
 /// Unfortunately, initial testing revealed that this approach is not nearly fast enough
 ///   10 000 < .5sec, while 100 000 > 10sec at best this implies 100 000 000 > 80000sec
 ///   even compiled, the code takes 22s for 10^6
-pub fn divisors_are_prime(n: usize, sieve: &primal::Sieve) -> bool {
+pub fn divisors_are_prime(n: usize, sieve: &SpfSieve<u32>) -> bool {
     // skip the following checks to save time, caller must ensure all n are even
     /*
     // n == 1 is a special case
@@ -148,32 +162,21 @@ pub fn divisors_are_prime(n: usize, sieve: &primal::Sieve) -> bool {
     }
     */
     // We know multiples of the squares (4,9,25,...) will fail, see proof above.
-    // Adding this test takes time on each n, but finding the divisors is significantly
-    // slower, so this saves time overall
-    let root_n = math::isqrt(n);
-    for i in 2..root_n {
-        let square = i * i;
-        if square > root_n {
-            break;
-        }
-        if n % square == 0 {
-            return false;
-        }
+    // With the prime factors in hand, this is simply a check for a repeated factor.
+    let factors = sieve.factor(n);
+    if !arith::is_squarefree(&factors) {
+        return false;
     }
     // Check only the lower divisors
-    // turns out this is slower than a filter on the iterator
-    // divisors_min(n, root_n, sieve).iter().all(|&d| sieve.is_prime(d + n/d))
-
-    // If I am checking all even numbers, then I need to check 1+n/1
-    //divisors(n, sieve).iter().filter(|&x| x <= &root_n).all(|&d| sieve.is_prime(d + n/d))
+    // The previous solutions generated all the divisors (with duplicates) and
+    // then filtered them.  Generating just the unique small divisors is faster.
+    //divisors(n, sieve).iter().filter(|x| **x > 1 && **x <= root_n).all(|&d| sieve.is_prime(d + n / d))
 
     // If I already know that n+1 is prime, then I can skip the check of 1 + n/1
     // This filter reduces the number of primal checks and potentially the size of the sieve
     // but beware sieve to sqrt(n) is not adequate.  e.g need to check is_prime(2 + n/2)
-    divisors(n, sieve)
-        .iter()
-        .filter(|x| **x > 1 && **x <= root_n)
-        .all(|&d| sieve.is_prime(d + n / d))
+    let mut pairs = divisors::divisor_pairs(&factors).filter(|&(d, _)| d > 1);
+    pairs.all(|(d, q)| sieve.is_prime(d + q))
 }
 
 /// Returns just the "small" divisors of a number
@@ -184,7 +187,7 @@ pub fn divisors_are_prime(n: usize, sieve: &primal::Sieve) -> bool {
 /// Unfortunately to do this we generate all the divisors, and then need to
 /// clone them into a new vector which is less efficient than filtering the
 /// full divisor list on the fly.
-fn divisors_min(n: usize, root_n: usize, sieve: &primal::Sieve) -> Vec<usize> {
+fn divisors_min(n: usize, root_n: usize, sieve: &SpfSieve<u32>) -> Vec<usize> {
     let d = divisors(n, sieve);
     d.iter().cloned().filter(|&x| x <= root_n).collect()
 }
@@ -198,8 +201,8 @@ fn divisors_min(n: usize, root_n: usize, sieve: &primal::Sieve) -> Vec<usize> {
 /// times pi is a factor of n.  This is expanded into a vector
 /// i.e. 36 => [(2,2),(3,2)] => [2,2,3,3].  A recursive "powerset"
 /// method is used to generate all the divisors from this vector,
-fn unique_divisors(n: usize, sieve: &primal::Sieve) -> HashSet<usize> {
-    let factors = sieve.factor(n).unwrap();
+fn unique_divisors(n: usize, sieve: &SpfSieve<u32>) -> HashSet<usize> {
+    let factors = sieve.factor(n);
     let mut all_factors: Vec<usize> = Vec::new();
     for (p, c) in factors.iter() {
         for _ in 0..*c {
@@ -239,8 +242,8 @@ fn unique_divisors_recursive(v: &mut Vec<usize>) -> HashSet<usize> {
 /// times pi is a factor of n.  This is expanded into a vector
 /// i.e. 12 => [(2,2),(3,1)] => [2,2,3].  A recursive "powerset"
 /// method is used to generate all the divisors of this vector.
-fn divisors(n: usize, sieve: &primal::Sieve) -> Vec<usize> {
-    let factors = sieve.factor(n).unwrap();
+fn divisors(n: usize, sieve: &SpfSieve<u32>) -> Vec<usize> {
+    let factors = sieve.factor(n);
     let mut all_factors: Vec<usize> = Vec::new();
     for (p, c) in factors.iter() {
         for _ in 0..*c {
@@ -279,4 +282,14 @@ mod tests {
     pub fn non_bouncy_test_2() {
         assert_eq!(sum_divisors_to(100), 401);
     }
+    #[test]
+    pub fn candidates_test() {
+        let n = 1_000_000;
+        let sieve = SpfSieve::compact(n + 1);
+        let candidates = sieve.primes().map(|p| p - 1).take_while(|&x| x <= n);
+        assert_eq!(
+            candidates.count() as u64,
+            summatory::prime_count(n as u64 + 1)
+        );
+    }
 }
@@ -12,6 +12,7 @@ mod euler;
 fn run_test() {
     euler::problem345::sample();
     //euler::math::fibonacci::benchmark();
+    //euler::math::montgomery::benchmark();
 }
 
 /// Run only one selected problem.
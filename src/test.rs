@@ -103,6 +103,7 @@ fn lattice(n: usize) -> usize {
     lattice(100000000); // -> 8 (68)
     lattice(1000000000); // -> 9 (76)
     lattice(10_000_000_000);  // fails due to overflow of n^2 in usize
+    // math::squares::circle_lattice_points(n) counts all of these (r2(2n^2)) for any u64 n
 */
 /*
 n = 10, n/2 = 5, r = 7.0710678118654755